@@ -0,0 +1,85 @@
+use chrono::NaiveDate;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use tempfile::TempDir;
+use zkmesh_fetcher::feed_types::EprintFetcher;
+use zkmesh_fetcher::http::HttpResponse;
+use zkmesh_fetcher::models::EprintConfig;
+use zkmesh_fetcher::replay::{FixtureMode, FixtureStore};
+use zkmesh_fetcher::{ArticleFetcher, DateWindow, FetchContext};
+
+const FEED_URL: &str = "https://eprint.iacr.org/oai?verb=ListRecords&metadataPrefix=oai_dc";
+const REQUEST_URL: &str = "https://eprint.iacr.org/oai?verb=ListRecords&metadataPrefix=oai_dc&from=2024-10-01";
+
+/// A single `ListRecords` page holding `count` records, like a large monthly harvest.
+fn list_records(count: usize) -> String {
+    let mut xml = String::from(r#"<?xml version="1.0" encoding="UTF-8"?>
+<OAI-PMH xmlns="http://www.openarchives.org/OAI/2.0/">
+  <responseDate>2024-09-27T19:53:51Z</responseDate>
+  <request verb="ListRecords" metadataPrefix="oai_dc">https://eprint.iacr.org/oai</request>
+  <ListRecords>"#);
+    for i in 0..count {
+        xml.push_str(&format!(r#"
+    <record>
+      <header>
+        <identifier>oai:eprint.iacr.org:2024/{i}</identifier>
+        <datestamp>2024-09-18T07:46:25Z</datestamp>
+      </header>
+      <metadata>
+        <oai_dc:dc xmlns:oai_dc="http://www.openarchives.org/OAI/2.0/oai_dc/" xmlns:dc="http://purl.org/dc/elements/1.1/">
+          <dc:identifier>https://eprint.iacr.org/2024/{i}</dc:identifier>
+          <dc:title>Paper number {i} on lattices &amp; folding schemes</dc:title>
+          <dc:creator>First Author {i}</dc:creator>
+          <dc:creator>Second Author</dc:creator>
+          <dc:date>2024-09-13T02:36:04Z</dc:date>
+          <dc:date>2024-09-14T02:36:04Z</dc:date>
+          <dc:description>{description}</dc:description>
+          <dc:subject>Cryptographic protocols</dc:subject>
+        </oai_dc:dc>
+      </metadata>
+    </record>"#, i = i, description = "We study commitment schemes and their applications to succinct arguments. ".repeat(8)));
+    }
+    xml.push_str("\n  </ListRecords>\n</OAI-PMH>\n");
+    xml
+}
+
+fn fixture(count: usize) -> TempDir {
+    let dir = TempDir::new().expect("Failed to create temporary directory");
+    let response = HttpResponse {
+        status: StatusCode::OK,
+        headers: HeaderMap::new(),
+        body: list_records(count).into_bytes(),
+    };
+    FixtureStore::new(dir.path()).save(REQUEST_URL, &response).expect("Failed to save fixture");
+    dir
+}
+
+fn bench_eprint_parsing(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().expect("Failed to start runtime");
+    // Records are dated before the threshold, so the benchmark measures parsing and date filtering
+    let window = DateWindow::since(NaiveDate::from_ymd_opt(2024, 10, 1).unwrap());
+
+    let mut group = c.benchmark_group("eprint_parsing");
+    group.sample_size(10);
+    for count in [1_000, 20_000] {
+        let dir = fixture(count);
+        let fetcher = EprintFetcher::new(&EprintConfig::default()).expect("Failed to build fetcher");
+        let ctx = FetchContext {
+            fixtures: Some(FixtureMode::Replay(FixtureStore::new(dir.path()))),
+            ..FetchContext::default()
+        };
+        group.throughput(Throughput::Elements(count as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(count), &count, |b, _| {
+            b.to_async(&runtime).iter(|| async {
+                fetcher.fetch_articles(&ctx, FEED_URL, &window, "Eprint", None)
+                    .await
+                    .expect("Failed to parse fixture")
+            });
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_eprint_parsing);
+criterion_main!(benches);
@@ -0,0 +1,216 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+use chrono_tz::Tz;
+use tokio::sync::Semaphore;
+use crate::feed_types::{FetchContext, FetcherRegistry};
+use crate::cache::HttpCache;
+use crate::context::{FetchAttempt, SkippedItem};
+use crate::http::HttpClient;
+use crate::rate_limit::{HostRateLimiter, RateLimitConfig};
+use crate::replay::FixtureMode;
+use crate::retry::RetryPolicy;
+use crate::models::{BlogInfo, BlogArticle};
+use crate::dates::{start_of_day, DateWindow};
+
+/// Name under which ePrint articles are reported, see [`FetchReport::eprint_articles`].
+pub const EPRINT_BLOG_NAME: &str = "Eprint";
+
+/// Name under which arXiv articles are reported, see [`FetchReport::arxiv_articles`].
+pub const ARXIV_BLOG_NAME: &str = "arXiv";
+
+/// Papers of one section of a [`FetchReport`], with the `top_n` of the source of each.
+#[derive(Default)]
+struct Section {
+    papers: Vec<BlogArticle>,
+    top_ns: Vec<Option<usize>>,
+}
+
+impl Section {
+    fn push(&mut self, paper: BlogArticle, top_n: Option<usize>) {
+        self.papers.push(paper);
+        self.top_ns.push(top_n);
+    }
+
+    /// Sorts the papers by relevance, lists each once with its best score, and
+    /// keeps as many as the most generous source asks for.
+    fn rank(self) -> Vec<BlogArticle> {
+        let mut papers = self.papers;
+        papers.sort_by(|a, b| {
+            b.score.unwrap_or(0.0).total_cmp(&a.score.unwrap_or(0.0)).then(a.published.cmp(&b.published))
+        });
+        // A paper matching several profiles is listed once, with its best score
+        let mut seen_papers = HashSet::new();
+        papers.retain(|article| seen_papers.insert(article.url.clone()));
+        // A source without `top_n` lists all of its papers
+        let top_n = self.top_ns.into_iter().collect::<Option<Vec<_>>>().and_then(|top_ns| top_ns.into_iter().max());
+        if let Some(top_n) = top_n {
+            papers.truncate(top_n);
+        }
+        papers
+    }
+}
+
+/// Fetches articles from a list of sources concurrently.
+pub struct Aggregator {
+    blogs: Vec<BlogInfo>,
+    context: FetchContext,
+    max_concurrent_sources: usize,
+}
+
+/// Outcome of an [`Aggregator::fetch`] run.
+#[derive(Debug, Default)]
+pub struct FetchReport {
+    /// ePrint papers published since the since date from every ePrint source,
+    /// by decreasing score then date, without duplicates. Each paper section is
+    /// cut to the largest `top_n` of the sources listed in it.
+    pub eprint_articles: Vec<BlogArticle>,
+    /// ePrint papers published earlier but revised since the since date, in the same order.
+    pub eprint_revisions: Vec<BlogArticle>,
+    /// arXiv papers submitted since the since date from every arXiv source, in the same order.
+    pub arxiv_articles: Vec<BlogArticle>,
+    /// Articles from every other source, sorted by publication time.
+    pub other_articles: Vec<BlogArticle>,
+    /// `(blog name, error message)` for every source that failed.
+    pub errors: Vec<(String, String)>,
+    /// `(blog name, attempt)` for every HTTP request made, retries included.
+    pub attempts: Vec<(String, FetchAttempt)>,
+    /// `(blog name, item)` for every entry left out because it could not be read.
+    pub skipped: Vec<(String, SkippedItem)>,
+    /// `(blog name, warning)` for every source fetched despite something odd, e.g. a mislabelled feed.
+    pub warnings: Vec<(String, String)>,
+    /// Timezone in which the dates of the articles are reported.
+    pub timezone: Tz,
+}
+
+impl Aggregator {
+    /// Creates an aggregator using the built-in fetchers.
+    pub fn new(blogs: Vec<BlogInfo>) -> Self {
+        Aggregator {
+            blogs,
+            context: FetchContext::default(),
+            max_concurrent_sources: RateLimitConfig::default().max_concurrent_sources,
+        }
+    }
+
+    /// Uses `registry` to build the fetcher of each source, e.g. to add custom feed types.
+    ///
+    /// `Auto` sources build the fetcher of the type they detect from it too.
+    pub fn with_registry(mut self, registry: FetcherRegistry) -> Self {
+        self.context.registry = Arc::new(registry);
+        self
+    }
+
+    /// Uses `http` for every request made by the fetchers.
+    pub fn with_http_client(mut self, http: HttpClient) -> Self {
+        self.context.http = http;
+        self
+    }
+
+    /// Uses `retry` for every source that does not set its own `max_attempts`.
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.context.retry = retry;
+        self
+    }
+
+    /// Caps the number of sources fetched at once and throttles requests per host.
+    pub fn with_rate_limit(mut self, config: &RateLimitConfig) -> Self {
+        self.context.limiter = HostRateLimiter::new(config);
+        self.max_concurrent_sources = config.max_concurrent_sources.max(1);
+        self
+    }
+
+    /// Revalidates responses against `cache` with `ETag` / `Last-Modified` conditional GETs.
+    pub fn with_cache(mut self, cache: HttpCache) -> Self {
+        self.context.cache = Some(cache);
+        self
+    }
+
+    /// Records every response to fixtures, or replays them without network access.
+    pub fn with_fixtures(mut self, fixtures: FixtureMode) -> Self {
+        self.context.fixtures = Some(fixtures);
+        self
+    }
+
+    /// Reports entry dates in `timezone`, UTC by default, and compares them to the since date there.
+    pub fn with_timezone(mut self, timezone: Tz) -> Self {
+        self.context.timezone = timezone;
+        self
+    }
+
+    /// The sources this aggregator fetches from.
+    pub fn blogs(&self) -> &[BlogInfo] {
+        &self.blogs
+    }
+
+    /// Fetches the articles of every source published within `window`.
+    ///
+    /// A failing source does not abort the run: its error is recorded in
+    /// [`FetchReport::errors`] and the other sources are still fetched.
+    /// At most `max_concurrent_sources` sources are fetched at the same time.
+    pub async fn fetch(&self, window: &DateWindow) -> FetchReport {
+        let mut tasks = Vec::new();
+        let mut report = FetchReport::default();
+        let semaphore = Arc::new(Semaphore::new(self.max_concurrent_sources));
+
+        for blog in &self.blogs {
+            let fetcher = match self.context.registry.build(blog) {
+                Ok(fetcher) => fetcher,
+                Err(e) => {
+                    report.errors.push((blog.name.clone(), e.to_string()));
+                    continue;
+                }
+            };
+
+            let top_n = fetcher.top_n();
+            let blog_clone = blog.clone();
+            let window = *window;
+            let ctx = self.context.for_source(blog);
+            let log = ctx.log.clone();
+            let semaphore = semaphore.clone();
+            let task = tokio::spawn(async move {
+                let _permit = semaphore.acquire_owned().await.expect("Fetch semaphore closed");
+                fetcher.fetch_articles(&ctx, &blog_clone.domain, &window, &blog_clone.name, blog_clone.custom_url_replace.clone()).await
+            });
+            tasks.push((task, blog.name.clone(), log, top_n));
+        }
+
+        let since = start_of_day(window.since, self.context.timezone);
+        report.timezone = self.context.timezone;
+        let (mut eprint_articles, mut eprint_revisions, mut arxiv_articles) = (Section::default(), Section::default(), Section::default());
+        for (task, blog_name, log, top_n) in tasks {
+            let result = task.await;
+            report.attempts.extend(log.attempts().into_iter().map(|attempt| (blog_name.clone(), attempt)));
+            report.skipped.extend(log.skipped().into_iter().map(|item| (blog_name.clone(), item)));
+            report.warnings.extend(log.warnings().into_iter().map(|warning| (blog_name.clone(), warning)));
+            match result {
+                Ok(Ok(articles)) => {
+                    for article in articles {
+                        if article.blog_name == EPRINT_BLOG_NAME && article.published < since {
+                            eprint_revisions.push(article, top_n);
+                        } else if article.blog_name == EPRINT_BLOG_NAME {
+                            eprint_articles.push(article, top_n);
+                        } else if article.blog_name == ARXIV_BLOG_NAME {
+                            arxiv_articles.push(article, top_n);
+                        } else {
+                            report.other_articles.push(article);
+                        }
+                    }
+                }
+                Ok(Err(e)) => {
+                    report.errors.push((blog_name, e.to_string()));
+                }
+                Err(e) => {
+                    report.errors.push((blog_name, e.to_string()));
+                }
+            }
+        }
+
+        // Rank papers by relevance, new ones apart from revisions, then sort articles by date
+        report.eprint_articles = eprint_articles.rank();
+        report.eprint_revisions = eprint_revisions.rank();
+        report.arxiv_articles = arxiv_articles.rank();
+        report.other_articles.sort_by_key(|a| a.published);
+
+        report
+    }
+}
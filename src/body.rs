@@ -0,0 +1,174 @@
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use bytes::Bytes;
+use futures::stream::{self, Stream, StreamExt};
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use tokio::io::AsyncBufRead;
+use tokio_util::io::{ReaderStream, StreamReader};
+use crate::errors::AppError;
+use crate::http::HttpResponse;
+
+/// Read size for bodies served from disk.
+const FILE_CHUNK_BYTES: usize = 64 * 1024;
+
+/// Response body delivered chunk by chunk as it arrives.
+pub type BodyStream = Pin<Box<dyn Stream<Item = io::Result<Bytes>> + Send>>;
+
+/// Response body readable as an async buffered reader, e.g. by an incremental XML parser.
+pub type BodyReader = Pin<Box<dyn AsyncBufRead + Send>>;
+
+/// A response whose body has not been read yet.
+pub struct StreamingResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: BodyStream,
+}
+
+impl StreamingResponse {
+    /// Reads the whole body into memory.
+    pub async fn buffer(self) -> Result<HttpResponse, AppError> {
+        let mut body = Vec::new();
+        let mut chunks = self.body;
+        while let Some(chunk) = chunks.next().await {
+            body.extend_from_slice(&chunk.map_err(body_error)?);
+        }
+        Ok(HttpResponse { status: self.status, headers: self.headers, body })
+    }
+
+    /// Returns the body as a reader.
+    pub fn into_reader(self) -> BodyReader {
+        Box::pin(StreamReader::new(self.body))
+    }
+}
+
+impl From<HttpResponse> for StreamingResponse {
+    /// Serves an already buffered body as a single chunk.
+    fn from(response: HttpResponse) -> Self {
+        let body = Bytes::from(response.body);
+        StreamingResponse {
+            status: response.status,
+            headers: response.headers,
+            body: Box::pin(stream::once(async move { Ok(body) })),
+        }
+    }
+}
+
+/// Streams the file at `path`.
+pub(crate) async fn file_stream(path: &Path) -> io::Result<BodyStream> {
+    let file = tokio::fs::File::open(path).await?;
+    Ok(Box::pin(ReaderStream::with_capacity(file, FILE_CHUNK_BYTES)))
+}
+
+/// Turns an error raised while reading a body back into an [`AppError`].
+pub(crate) fn body_error(err: io::Error) -> AppError {
+    if err.get_ref().is_none() {
+        return AppError::IoError(err);
+    }
+    let kind = err.kind();
+    match err.into_inner().map(|inner| inner.downcast::<reqwest::Error>()) {
+        Some(Ok(e)) => AppError::NetworkError(*e),
+        // Errors raised by the body stream itself, such as the size limit
+        Some(Err(inner)) if kind == io::ErrorKind::Other => AppError::HttpError(inner.to_string()),
+        Some(Err(inner)) => AppError::IoError(io::Error::new(kind, inner)),
+        None => AppError::IoError(kind.into()),
+    }
+}
+
+static NEXT_WRITE_ID: AtomicU64 = AtomicU64::new(0);
+
+/// A body being copied to disk while it is read.
+///
+/// Chunks go to a temporary file which only replaces `body_path` once the
+/// whole body was read, followed by `meta`; an abandoned write leaves the
+/// previous entry untouched.
+pub(crate) struct PendingWrite {
+    file: Option<File>,
+    tmp_path: PathBuf,
+    body_path: PathBuf,
+    meta_path: PathBuf,
+    meta: Vec<u8>,
+    /// Whether failing to write aborts the read, as for fixtures, or only drops the copy, as for the cache.
+    required: bool,
+}
+
+impl PendingWrite {
+    pub(crate) fn create(body_path: PathBuf, meta_path: PathBuf, meta: Vec<u8>, required: bool) -> io::Result<Self> {
+        if let Some(dir) = body_path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        // Sources fetching the same URL at the same time each get their own file
+        let id = NEXT_WRITE_ID.fetch_add(1, Ordering::Relaxed);
+        let tmp_path = body_path.with_extension(format!("body.{}-{}.tmp", std::process::id(), id));
+        let file = File::create(&tmp_path)?;
+        Ok(PendingWrite { file: Some(file), tmp_path, body_path, meta_path, meta, required })
+    }
+
+    pub(crate) fn write(&mut self, chunk: &[u8]) -> io::Result<()> {
+        match self.file.as_mut() {
+            Some(file) => file.write_all(chunk),
+            None => Ok(()),
+        }
+    }
+
+    pub(crate) fn commit(mut self) -> io::Result<()> {
+        if let Some(mut file) = self.file.take() {
+            file.flush()?;
+            drop(file);
+            fs::rename(&self.tmp_path, &self.body_path)?;
+            fs::write(&self.meta_path, &self.meta)?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for PendingWrite {
+    fn drop(&mut self) {
+        if self.file.take().is_some() {
+            let _ = fs::remove_file(&self.tmp_path);
+        }
+    }
+}
+
+/// Copies `body` into `writes` as it is read, committing them once the body ends.
+pub(crate) fn tee(body: BodyStream, writes: Vec<PendingWrite>) -> BodyStream {
+    if writes.is_empty() {
+        return body;
+    }
+    Box::pin(stream::unfold(Some((body, writes)), |state| async move {
+        let (mut body, mut writes) = state?;
+        match body.next().await {
+            Some(Ok(chunk)) => {
+                let mut failed = None;
+                writes.retain_mut(|write| match write.write(&chunk) {
+                    Ok(()) => true,
+                    Err(e) => {
+                        if write.required {
+                            failed = Some(e);
+                        }
+                        false
+                    }
+                });
+                match failed {
+                    Some(e) => Some((Err(e), None)),
+                    None => Some((Ok(chunk), Some((body, writes)))),
+                }
+            }
+            Some(Err(e)) => Some((Err(e), None)),
+            None => {
+                for write in writes {
+                    let required = write.required;
+                    if let Err(e) = write.commit() {
+                        if required {
+                            return Some((Err(e), None));
+                        }
+                    }
+                }
+                None
+            }
+        }
+    }))
+}
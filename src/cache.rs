@@ -0,0 +1,115 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use reqwest::header::{HeaderMap, HeaderValue, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use serde::{Deserialize, Serialize};
+use crate::body::{file_stream, BodyStream, PendingWrite};
+
+/// HTTP cache settings, read from the `cache` section of the settings file.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct CacheConfig {
+    pub enabled: bool,
+    pub dir: String,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        CacheConfig {
+            enabled: false,
+            dir: "./cache/http".to_string(),
+        }
+    }
+}
+
+fn conditional_headers(etag: Option<&str>, last_modified: Option<&str>) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    if let Some(value) = etag.and_then(|etag| HeaderValue::from_str(etag).ok()) {
+        headers.insert(IF_NONE_MATCH, value);
+    }
+    if let Some(value) = last_modified.and_then(|date| HeaderValue::from_str(date).ok()) {
+        headers.insert(IF_MODIFIED_SINCE, value);
+    }
+    headers
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheEntry {
+    url: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+/// On-disk cache of responses keyed by URL, used for conditional GETs.
+///
+/// Each URL is stored as `<key>.json` (URL and validators) next to `<key>.body`.
+#[derive(Debug, Clone)]
+pub struct HttpCache {
+    dir: PathBuf,
+}
+
+impl HttpCache {
+    pub fn new(dir: impl AsRef<Path>) -> Self {
+        HttpCache { dir: dir.as_ref().to_path_buf() }
+    }
+
+    /// Headers revalidating the cached response for `url`, if there is one.
+    pub(crate) fn conditional_headers(&self, url: &str) -> Option<HeaderMap> {
+        let entry = self.entry(url)?;
+        if !self.paths(url).1.exists() {
+            return None;
+        }
+        Some(conditional_headers(entry.etag.as_deref(), entry.last_modified.as_deref()))
+    }
+
+    /// Streams the cached body for `url`.
+    pub(crate) async fn open_body(&self, url: &str) -> io::Result<BodyStream> {
+        file_stream(&self.paths(url).1).await
+    }
+
+    /// Starts caching a response with `headers` for `url`, if it carries a validator.
+    pub(crate) fn begin_store(&self, url: &str, headers: &HeaderMap) -> Option<PendingWrite> {
+        let entry = new_entry(url, headers)?;
+        let meta = serde_json::to_vec_pretty(&entry).ok()?;
+        let (meta_path, body_path) = self.paths(url);
+        PendingWrite::create(body_path, meta_path, meta, false).ok()
+    }
+
+    fn entry(&self, url: &str) -> Option<CacheEntry> {
+        let entry: CacheEntry = serde_json::from_slice(&fs::read(self.paths(url).0).ok()?).ok()?;
+        if entry.url != url {
+            return None;
+        }
+        Some(entry)
+    }
+
+    fn paths(&self, url: &str) -> (PathBuf, PathBuf) {
+        let key = cache_key(url);
+        (self.dir.join(format!("{}.json", key)), self.dir.join(format!("{}.body", key)))
+    }
+}
+
+fn new_entry(url: &str, headers: &HeaderMap) -> Option<CacheEntry> {
+    let header = |name| headers.get(name)
+        .and_then(|value: &HeaderValue| value.to_str().ok())
+        .map(str::to_string);
+    let entry = CacheEntry {
+        url: url.to_string(),
+        etag: header(ETAG),
+        last_modified: header(LAST_MODIFIED),
+    };
+    if entry.etag.is_none() && entry.last_modified.is_none() {
+        return None;
+    }
+    Some(entry)
+}
+
+/// FNV-1a hash of `url`, stable across runs and Rust versions.
+pub(crate) fn cache_key(url: &str) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in url.as_bytes() {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}", hash)
+}
@@ -1,8 +1,10 @@
 use std::fs::File;
-use std::io::BufReader;
+use std::io::{self, BufReader};
 use crate::errors::AppError;
-use crate::models::{BlogInfo, EprintConfig};
-use serde_json;
+use crate::models::{BlogInfo, EprintConfig, Settings};
+
+/// Search config of ePrint sources that set no `eprint_config` of their own.
+pub const DEFAULT_EPRINT_CONFIG_FILE: &str = "./config/eprint_search.json";
 
 pub fn read_blogs_from_file(filename: &str) -> Result<Vec<BlogInfo>, AppError> {
     let file = File::open(filename)?;
@@ -12,8 +14,17 @@ pub fn read_blogs_from_file(filename: &str) -> Result<Vec<BlogInfo>, AppError> {
     Ok(blogs)
 }
 
-pub fn load_eprint_config() -> Result<EprintConfig, AppError> {
-    let file = File::open("config/eprint_search.json")?;
+pub fn read_settings_from_file(filename: &str) -> Result<Settings, AppError> {
+    let file = File::open(filename)?;
+    let reader = BufReader::new(file);
+    let settings: Settings = serde_json::from_reader(reader)
+        .map_err(|e| AppError::ParseError(format!("Failed to parse settings JSON: {}", e)))?;
+    Ok(settings)
+}
+
+pub fn load_eprint_config(filename: &str) -> Result<EprintConfig, AppError> {
+    let file = File::open(filename)
+        .map_err(|e| AppError::IoError(io::Error::new(e.kind(), format!("Failed to open ePrint config {}: {}", filename, e))))?;
     let reader = BufReader::new(file);
     let config: EprintConfig = serde_json::from_reader(reader)
         .map_err(|e| AppError::ParseError(format!("Failed to parse ePrint JSON: {}", e)))?;
@@ -0,0 +1,264 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use chrono_tz::Tz;
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use crate::body::{tee, BodyReader, StreamingResponse};
+use crate::cache::HttpCache;
+use crate::errors::AppError;
+use crate::feed_types::FetcherRegistry;
+use crate::http::{HttpClient, HttpResponse};
+use crate::models::BlogInfo;
+use crate::rate_limit::HostRateLimiter;
+use crate::replay::FixtureMode;
+use crate::retry::{is_retryable_error, is_retryable_status, parse_retry_after, RetryPolicy};
+
+/// One HTTP request made while fetching a source.
+#[derive(Debug, Clone)]
+pub struct FetchAttempt {
+    pub url: String,
+    /// 1-based attempt number for this URL.
+    pub attempt: u32,
+    /// Response status or error, e.g. `200 OK` or `503 Service Unavailable`.
+    pub outcome: String,
+    /// Wait before the next attempt, if the request is retried.
+    pub retry_in: Option<Duration>,
+}
+
+/// An entry of a feed that could not be read and was left out.
+#[derive(Debug, Clone)]
+pub struct SkippedItem {
+    /// Identifier of the entry, or its position when it has none.
+    pub item: String,
+    pub reason: String,
+}
+
+/// Collects what happened while fetching one source, for the run report.
+#[derive(Debug, Clone, Default)]
+pub struct FetchLog {
+    attempts: Arc<Mutex<Vec<FetchAttempt>>>,
+    skipped: Arc<Mutex<Vec<SkippedItem>>>,
+    warnings: Arc<Mutex<Vec<String>>>,
+}
+
+impl FetchLog {
+    pub fn record_attempt(&self, attempt: FetchAttempt) {
+        self.attempts.lock().expect("Fetch log lock poisoned").push(attempt);
+    }
+
+    pub fn attempts(&self) -> Vec<FetchAttempt> {
+        self.attempts.lock().expect("Fetch log lock poisoned").clone()
+    }
+
+    pub fn record_skipped(&self, item: SkippedItem) {
+        self.skipped.lock().expect("Fetch log lock poisoned").push(item);
+    }
+
+    pub fn skipped(&self) -> Vec<SkippedItem> {
+        self.skipped.lock().expect("Fetch log lock poisoned").clone()
+    }
+
+    /// Records something odd about the source that did not stop it from being fetched.
+    pub fn record_warning(&self, warning: String) {
+        self.warnings.lock().expect("Fetch log lock poisoned").push(warning);
+    }
+
+    pub fn warnings(&self) -> Vec<String> {
+        self.warnings.lock().expect("Fetch log lock poisoned").clone()
+    }
+}
+
+/// Shared state handed to every fetcher for a run.
+///
+/// Fetchers should make their requests through [`FetchContext::get_bytes`],
+/// [`FetchContext::get_text`] or [`FetchContext::get_reader`], which apply the
+/// retry policy and log every attempt.
+#[derive(Debug, Clone, Default)]
+pub struct FetchContext {
+    pub http: HttpClient,
+    pub retry: RetryPolicy,
+    /// Shared by all sources of a run, so that requests to one host are throttled together.
+    pub limiter: HostRateLimiter,
+    /// When set, responses are revalidated with conditional GETs.
+    pub cache: Option<HttpCache>,
+    /// When set, responses are recorded to or replayed from fixtures.
+    pub fixtures: Option<FixtureMode>,
+    /// Timezone in which entry dates are reported and compared to the since date.
+    pub timezone: Tz,
+    /// Builds the fetchers of `Auto` sources once their feed type is detected.
+    pub registry: Arc<FetcherRegistry>,
+    /// Responses already downloaded for this source, served again without a request.
+    pub prefetched: Arc<HashMap<String, HttpResponse>>,
+    pub log: FetchLog,
+}
+
+impl FetchContext {
+    pub fn new(http: HttpClient) -> Self {
+        FetchContext { http, ..FetchContext::default() }
+    }
+
+    /// Context for fetching `blog`: a fresh log and the source's retry overrides.
+    pub fn for_source(&self, blog: &BlogInfo) -> Self {
+        let mut retry = self.retry.clone();
+        if let Some(max_attempts) = blog.max_attempts {
+            retry.max_attempts = max_attempts;
+        }
+        FetchContext {
+            http: self.http.clone(),
+            retry,
+            limiter: self.limiter.clone(),
+            cache: self.cache.clone(),
+            fixtures: self.fixtures.clone(),
+            timezone: self.timezone,
+            registry: self.registry.clone(),
+            prefetched: Arc::default(),
+            log: FetchLog::default(),
+        }
+    }
+
+    /// Fetches `url`, retrying transient failures, and returns the response body.
+    pub async fn get_bytes(&self, url: &str) -> Result<Vec<u8>, AppError> {
+        self.get(url).await?.into_body(url)
+    }
+
+    /// Like [`FetchContext::get_bytes`], returning the body as text with invalid UTF-8 replaced.
+    pub async fn get_text(&self, url: &str) -> Result<String, AppError> {
+        let body = self.get_bytes(url).await?;
+        Ok(String::from_utf8_lossy(&body).into_owned())
+    }
+
+    /// Like [`FetchContext::get_bytes`], returning a reader over the body as it arrives.
+    pub async fn get_reader(&self, url: &str) -> Result<BodyReader, AppError> {
+        let response = self.open(url).await?;
+        if response.status.is_success() {
+            return Ok(response.into_reader());
+        }
+        // Read the body anyway, so that error responses are recorded too
+        let status = response.status;
+        response.buffer().await?;
+        Err(AppError::HttpError(format!("{} returned {}", url, status)))
+    }
+
+    /// Fetches `url`, retrying transient failures, and returns the last response.
+    ///
+    /// The body is read within each attempt, so a download cut short is retried too.
+    pub async fn get(&self, url: &str) -> Result<HttpResponse, AppError> {
+        self.open_with(url, true).await?.buffer().await
+    }
+
+    /// Like [`FetchContext::get`], leaving the body to be read by the caller.
+    ///
+    /// Only failures before the body starts are retried.
+    /// With a cache, a `304 Not Modified` answer is returned as a `200 OK`
+    /// carrying the cached body, so parsers never see the difference.
+    /// In replay mode the recorded response is returned without any request.
+    /// Cache entries and recorded fixtures are written as the body is read,
+    /// and only kept once it was read to the end.
+    pub async fn open(&self, url: &str) -> Result<StreamingResponse, AppError> {
+        self.open_with(url, false).await
+    }
+
+    /// Opens `url`, reading the whole body within the retry loop when `buffered`.
+    async fn open_with(&self, url: &str, buffered: bool) -> Result<StreamingResponse, AppError> {
+        if let Some(response) = self.prefetched.get(url) {
+            return Ok(response.clone().into());
+        }
+        if let Some(FixtureMode::Replay(store)) = &self.fixtures {
+            let result = store.open(url).await;
+            self.log.record_attempt(FetchAttempt {
+                url: url.to_string(),
+                attempt: 1,
+                outcome: match &result {
+                    Ok(response) => format!("{} (replayed)", response.status),
+                    Err(e) => e.to_string(),
+                },
+                retry_in: None,
+            });
+            return result;
+        }
+
+        let conditional = self.cache.as_ref().and_then(|cache| cache.conditional_headers(url));
+        let revalidating = conditional.is_some();
+
+        self.open_with_retry(url, conditional.unwrap_or_default(), revalidating, buffered).await
+    }
+
+    /// Serves a `304 Not Modified` from the cache and copies the body to the cache and fixtures.
+    async fn prepare(&self, url: &str, mut response: StreamingResponse, revalidating: bool) -> Result<StreamingResponse, AppError> {
+        let mut writes = Vec::new();
+        match &self.cache {
+            Some(cache) if revalidating && response.status == StatusCode::NOT_MODIFIED => {
+                response.status = StatusCode::OK;
+                response.body = cache.open_body(url).await?;
+            }
+            // A failed write only costs a full download on the next run
+            Some(cache) if response.status.is_success() => writes.extend(cache.begin_store(url, &response.headers)),
+            _ => {}
+        }
+
+        if let Some(FixtureMode::Record(store)) = &self.fixtures {
+            writes.push(store.begin_save(url, response.status, &response.headers)?);
+        }
+        response.body = tee(response.body, writes);
+        Ok(response)
+    }
+
+    /// Sends `headers` to `url` until a response is final or attempts run out.
+    ///
+    /// Every attempt waits for the per-host rate limiter first. When `buffered`,
+    /// the body of a final response is read before deciding whether to retry;
+    /// otherwise only failures before the body starts are retried.
+    async fn open_with_retry(&self, url: &str, headers: HeaderMap, revalidating: bool, buffered: bool) -> Result<StreamingResponse, AppError> {
+        let max_attempts = self.retry.max_attempts.max(1);
+        let mut attempt = 1;
+        loop {
+            self.limiter.acquire(url).await;
+            let result = self.http.open(url, headers.clone()).await;
+            let (mut outcome, mut retryable, retry_after) = match &result {
+                Ok(response) => (
+                    response.status.to_string(),
+                    is_retryable_status(response.status),
+                    parse_retry_after(&response.headers),
+                ),
+                Err(AppError::NetworkError(e)) => (e.to_string(), is_retryable_error(e), None),
+                Err(e) => (e.to_string(), false, None),
+            };
+
+            let mut result = match result {
+                Ok(response) => self.prepare(url, response, revalidating).await,
+                Err(e) => Err(e),
+            };
+            if buffered && !retryable {
+                if let Ok(response) = result {
+                    result = response.buffer().await.map(StreamingResponse::from);
+                    (outcome, retryable) = match &result {
+                        Ok(_) => (outcome, retryable),
+                        Err(AppError::NetworkError(e)) => (e.to_string(), is_retryable_error(e)),
+                        Err(e) => (e.to_string(), false),
+                    };
+                }
+            }
+
+            let retry_in = if retryable && attempt < max_attempts {
+                Some(self.retry.delay(attempt, retry_after))
+            } else {
+                None
+            };
+            self.log.record_attempt(FetchAttempt {
+                url: url.to_string(),
+                attempt,
+                outcome,
+                retry_in,
+            });
+
+            match retry_in {
+                Some(delay) => {
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                None => return result,
+            }
+        }
+    }
+}
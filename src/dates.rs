@@ -0,0 +1,247 @@
+//! Dates of feed entries, read into UTC timestamps and reported in one timezone.
+//!
+//! Sources write dates in many ways: RFC 3339 in Atom and JSON Feed, RFC 2822
+//! in RSS (often loosely, e.g. with a full month name or no weekday), plain
+//! ISO 8601 in APIs and HTML pages. [`parse_datetime`] accepts all of them.
+//! The days fetched form a [`DateWindow`] of the reporting timezone, so an
+//! entry is compared to it by its [`report_date`].
+
+use std::fmt;
+use chrono::{DateTime, Datelike, Days, FixedOffset, Months, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use crate::errors::AppError;
+
+/// Zones written as abbreviations in RSS dates, with their offset in hours.
+const ZONE_ABBREVIATIONS: [(&str, i32); 17] = [
+    ("UT", 0), ("UTC", 0), ("GMT", 0), ("Z", 0),
+    ("EST", -5), ("EDT", -4), ("CST", -6), ("CDT", -5),
+    ("MST", -7), ("MDT", -6), ("PST", -8), ("PDT", -7),
+    ("BST", 1), ("CET", 1), ("CEST", 2), ("EET", 2), ("EEST", 3),
+];
+
+/// ISO 8601 layouts with a zone, tried after RFC 3339.
+const ISO_FORMATS_WITH_ZONE: [&str; 4] = [
+    "%Y-%m-%dT%H:%M:%S%.f%z",
+    "%Y-%m-%d %H:%M:%S%.f%z",
+    "%Y-%m-%dT%H:%M%z",
+    "%Y%m%dT%H%M%S%z",
+];
+
+/// ISO 8601 layouts without a zone, read in the reporting timezone.
+const ISO_FORMATS: [&str; 5] = [
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M",
+    "%Y-%m-%d %H:%M",
+    "%Y%m%dT%H%M%S",
+];
+
+/// Days whose entries are fetched, both ends included, in the reporting timezone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DateWindow {
+    pub since: NaiveDate,
+    /// Last day fetched, or `None` to fetch up to now.
+    pub until: Option<NaiveDate>,
+}
+
+impl DateWindow {
+    /// Window from `since` to now.
+    pub fn since(since: NaiveDate) -> Self {
+        DateWindow { since, until: None }
+    }
+
+    /// Window from `since` to `until`, which must not be before `since`.
+    pub fn new(since: NaiveDate, until: Option<NaiveDate>) -> Result<Self, AppError> {
+        match until {
+            Some(until) if until < since => Err(AppError::ParseError(format!("Since date {} is after until date {}", since, until))),
+            _ => Ok(DateWindow { since, until }),
+        }
+    }
+
+    /// The whole of month `month` of `year`.
+    pub fn month(year: i32, month: u32) -> Result<Self, AppError> {
+        let since = NaiveDate::from_ymd_opt(year, month, 1)
+            .ok_or_else(|| AppError::ParseError(format!("Invalid month: {}-{:02}", year, month)))?;
+        let until = since.checked_add_months(Months::new(1)).and_then(|next| next.pred_opt());
+        Ok(DateWindow { since, until })
+    }
+
+    /// Monday to Sunday of the week before the one of `today`.
+    pub fn last_week(today: NaiveDate) -> Self {
+        let monday = today - Days::new(u64::from(today.weekday().num_days_from_monday()) + 7);
+        DateWindow { since: monday, until: Some(monday + Days::new(6)) }
+    }
+
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        date >= self.since && self.until.is_none_or(|until| date <= until)
+    }
+
+    /// Whether `date` is after the last day of the window.
+    pub fn is_after(&self, date: NaiveDate) -> bool {
+        self.until.is_some_and(|until| date > until)
+    }
+}
+
+impl fmt::Display for DateWindow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.until {
+            Some(until) => write!(f, "{} to {}", self.since, until),
+            None => write!(f, "since {}", self.since),
+        }
+    }
+}
+
+/// Parses a date written as RFC 3339, RFC 2822 or one of its RFC 822
+/// variants, or ISO 8601, with or without a time and zone.
+///
+/// A date without a zone is taken to be in `timezone`, and one without a
+/// time to be at midnight there, so that it is reported on the day written.
+pub fn parse_datetime(value: &str, timezone: Tz) -> Result<DateTime<Utc>, AppError> {
+    let value = value.trim();
+    if let Ok(date) = DateTime::parse_from_rfc3339(value) {
+        return Ok(date.with_timezone(&Utc));
+    }
+    if let Ok(date) = DateTime::parse_from_rfc2822(value) {
+        return Ok(date.with_timezone(&Utc));
+    }
+    parse_iso8601(value, timezone)
+        .or_else(|| parse_rfc822(value, timezone))
+        .ok_or_else(|| AppError::ParseError(format!("Unable to parse date: {}", value)))
+}
+
+/// Calendar day of `at` in `timezone`, which is compared to the since date.
+pub fn report_date(at: DateTime<Utc>, timezone: Tz) -> NaiveDate {
+    at.with_timezone(&timezone).date_naive()
+}
+
+/// First instant of `date` in `timezone`, where the since date starts.
+pub fn start_of_day(date: NaiveDate, timezone: Tz) -> DateTime<Utc> {
+    in_timezone(date.and_time(NaiveTime::MIN), timezone)
+}
+
+/// `date` at `time` in `timezone`. A time skipped by a daylight saving change
+/// is read as UTC rather than rejected.
+fn in_timezone(date: NaiveDateTime, timezone: Tz) -> DateTime<Utc> {
+    timezone.from_local_datetime(&date).earliest()
+        .map(|date| date.with_timezone(&Utc))
+        .unwrap_or_else(|| Utc.from_utc_datetime(&date))
+}
+
+fn parse_iso8601(value: &str, timezone: Tz) -> Option<DateTime<Utc>> {
+    // `%z` does not read the `Z` of UTC
+    let zoned = match value.strip_suffix(['Z', 'z']) {
+        Some(value) => format!("{}+00:00", value),
+        None => value.to_string(),
+    };
+    if let Some(date) = ISO_FORMATS_WITH_ZONE.iter().find_map(|format| DateTime::parse_from_str(&zoned, format).ok()) {
+        return Some(date.with_timezone(&Utc));
+    }
+    if let Some((value, offset)) = split_zone(value) {
+        let date = ISO_FORMATS.iter().find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())?;
+        return Some(offset.from_local_datetime(&date).single()?.with_timezone(&Utc));
+    }
+    if let Some(date) = ISO_FORMATS.iter().find_map(|format| NaiveDateTime::parse_from_str(value, format).ok()) {
+        return Some(in_timezone(date, timezone));
+    }
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()
+        .map(|date| start_of_day(date, timezone))
+}
+
+/// Splits off the zones of an ISO 8601 date that `%z` does not read: an
+/// hour-only offset (`12:00:00+02`) or a zone after a space (`12:00:00 GMT+2`).
+fn split_zone(value: &str) -> Option<(&str, FixedOffset)> {
+    if let Some((date, zone)) = value.rsplit_once(' ') {
+        if let Some(offset) = zone_offset(zone) {
+            return Some((date.trim_end(), offset));
+        }
+    }
+    let sign = value.rfind(['+', '-'])?;
+    let (date, zone) = value.split_at(sign);
+    // The `-` of the date itself is not a zone
+    if !date.contains(':') || zone.len() != 3 {
+        return None;
+    }
+    Some((date, zone_offset(zone)?))
+}
+
+/// Reads the RFC 822 dates that RFC 2822 parsing rejects: full or misspelt
+/// weekday and month names, times without seconds, zone abbreviations other
+/// than the North American ones, or no zone at all.
+fn parse_rfc822(value: &str, timezone: Tz) -> Option<DateTime<Utc>> {
+    // The weekday is redundant, and often wrong
+    let value = match value.split_once(',') {
+        Some((weekday, rest)) if weekday.chars().all(char::is_alphabetic) => rest,
+        _ => value,
+    };
+    let mut parts = value.split_whitespace();
+    let day: u32 = parts.next()?.parse().ok()?;
+    let month = month_number(parts.next()?)?;
+    let year = match parts.next()? {
+        year if year.len() == 2 => {
+            // RFC 822 two-digit years, read as RFC 2822 does
+            let year: i32 = year.parse().ok()?;
+            if year < 50 { 2000 + year } else { 1900 + year }
+        }
+        year => year.parse().ok()?,
+    };
+    let date = NaiveDate::from_ymd_opt(year, month, day)?;
+    let time = match parts.next() {
+        Some(time) => NaiveTime::parse_from_str(time, "%H:%M:%S")
+            .or_else(|_| NaiveTime::parse_from_str(time, "%H:%M"))
+            .ok()?,
+        None => NaiveTime::MIN,
+    };
+    let date = date.and_time(time);
+
+    match parts.next() {
+        None => Some(in_timezone(date, timezone)),
+        Some(zone) if parts.next().is_none() => {
+            let offset = zone_offset(zone)?;
+            Some(offset.from_local_datetime(&date).single()?.with_timezone(&Utc))
+        }
+        Some(_) => None,
+    }
+}
+
+fn month_number(name: &str) -> Option<u32> {
+    const MONTHS: [&str; 12] = ["jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec"];
+    let name = name.trim_end_matches('.').to_ascii_lowercase();
+    let position = MONTHS.iter().position(|month| name.len() >= 3 && name.starts_with(month))?;
+    Some(position as u32 + 1)
+}
+
+/// Offset of a `+hh`, `+hhmm` or `+hh:mm` zone, of a known abbreviation, or
+/// of an offset from GMT or UTC such as `GMT+2` or `UTC-05:30`.
+fn zone_offset(zone: &str) -> Option<FixedOffset> {
+    if let Some((_, hours)) = ZONE_ABBREVIATIONS.iter().find(|(name, _)| name.eq_ignore_ascii_case(zone)) {
+        return FixedOffset::east_opt(hours * 3600);
+    }
+    let upper = zone.to_ascii_uppercase();
+    let offset_start = ["GMT", "UTC", "UT"].iter()
+        .find_map(|prefix| upper.strip_prefix(prefix).filter(|rest| rest.starts_with(['+', '-'])))
+        .map_or(0, |rest| zone.len() - rest.len());
+    let zone = &zone[offset_start..];
+    let sign = match zone.chars().next()? {
+        '+' => 1,
+        '-' => -1,
+        _ => return None,
+    };
+    let digits = &zone[1..];
+    if !digits.bytes().all(|b| b.is_ascii_digit() || b == b':') {
+        return None;
+    }
+    let (hours, minutes) = match digits.split_once(':') {
+        Some((hours, minutes)) => (hours, minutes),
+        None if digits.len() == 4 => digits.split_at(2),
+        None => (digits, "00"),
+    };
+    if !(1..=2).contains(&hours.len()) || minutes.len() != 2 || minutes.contains(':') {
+        return None;
+    }
+    let hours: i32 = hours.parse().ok()?;
+    let minutes: i32 = minutes.parse().ok()?;
+    if minutes >= 60 {
+        return None;
+    }
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
@@ -1,8 +1,4 @@
 use std::fmt;
-use atom_syndication;
-use rss;
-use tokio;
-use xml; 
 
 #[derive(Debug)]
 pub enum AppError {
@@ -12,7 +8,8 @@ pub enum AppError {
     IoError(std::io::Error),
     UnknownFeedType(String),
     AsyncRuntimeError(tokio::task::JoinError),
-    XmlError(xml::reader::Error), 
+    HttpError(String),
+    XmlParseError(quick_xml::Error),
 }
 
 impl std::error::Error for AppError {}
@@ -26,7 +23,8 @@ impl fmt::Display for AppError {
             AppError::IoError(e) => write!(f, "IO error: {}", e),
             AppError::UnknownFeedType(t) => write!(f, "Unknown feed type: {}", t),
             AppError::AsyncRuntimeError(e) => write!(f, "Async runtime error: {}", e),
-            AppError::XmlError(e) => write!(f, "XML error: {}", e), 
+            AppError::HttpError(e) => write!(f, "HTTP error: {}", e),
+            AppError::XmlParseError(e) => write!(f, "XML parse error: {}", e),
         }
     }
 }
@@ -61,20 +59,14 @@ impl From<atom_syndication::Error> for AppError {
     }
 }
 
-impl From<rss::Error> for AppError {
-    fn from(err: rss::Error) -> Self {
-        AppError::ParseError(err.to_string())
-    }
-}
-
 impl From<tokio::task::JoinError> for AppError {
     fn from(err: tokio::task::JoinError) -> Self {
         AppError::AsyncRuntimeError(err)
     }
 }
 
-impl From<xml::reader::Error> for AppError {
-    fn from(err: xml::reader::Error) -> Self {
-        AppError::XmlError(err)
+impl From<quick_xml::Error> for AppError {
+    fn from(err: quick_xml::Error) -> Self {
+        AppError::XmlParseError(err)
     }
-}
\ No newline at end of file
+}
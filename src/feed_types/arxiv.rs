@@ -0,0 +1,278 @@
+use chrono::{Days, TimeDelta, Utc};
+use chrono_tz::Tz;
+use async_trait::async_trait;
+use atom_syndication::{Entry, Feed};
+use reqwest::Url;
+use super::{ArticleFetcher, FetchContext, FromBlogInfo};
+use crate::context::SkippedItem;
+use crate::models::{BlogArticle, BlogInfo, EprintConfig};
+use crate::errors::AppError;
+use crate::dates::{report_date, start_of_day, DateWindow};
+use crate::filter::{Paper, PaperFilter};
+use crate::config::{load_eprint_config, DEFAULT_EPRINT_CONFIG_FILE};
+use crate::aggregator::ARXIV_BLOG_NAME;
+use crate::utils::format_authors;
+
+/// Entries requested per page of the arXiv API.
+const PAGE_SIZE: usize = 100;
+
+/// Upper bound on requested pages, in case a query keeps matching older papers.
+const MAX_PAGES: usize = 50;
+
+/// Fetches arXiv papers matching a search config, newest submissions first.
+///
+/// The arXiv API is queried for the config's `categories`, narrowed down to
+/// its plain keywords and followed authors; every paper is then scored by the
+/// same [`PaperFilter`] as ePrint papers.
+pub struct ArxivFetcher {
+    filter: PaperFilter,
+    search_query: String,
+    page_size: usize,
+    max_pages: usize,
+}
+
+impl FromBlogInfo for ArxivFetcher {
+    /// Loads the search config from the source's `eprint_config`, or the default file.
+    fn from_blog_info(blog: &BlogInfo) -> Result<Self, AppError> {
+        let path = blog.eprint_config.as_deref().unwrap_or(DEFAULT_EPRINT_CONFIG_FILE);
+        ArxivFetcher::new(&load_eprint_config(path)?)
+    }
+}
+
+impl ArxivFetcher {
+    /// Fetcher keeping the papers selected by `config`. A config without
+    /// categories, keywords nor authors would match all of arXiv and is an error.
+    pub fn new(config: &EprintConfig) -> Result<Self, AppError> {
+        Ok(ArxivFetcher {
+            filter: PaperFilter::new(config)?,
+            search_query: search_query(config)?,
+            page_size: PAGE_SIZE,
+            max_pages: MAX_PAGES,
+        })
+    }
+
+    /// Requests `page_size` entries per page instead of 100.
+    pub fn with_page_size(mut self, page_size: usize) -> Self {
+        self.page_size = page_size.max(1);
+        self
+    }
+
+    /// Requests at most `max_pages` pages instead of 50.
+    pub fn with_max_pages(mut self, max_pages: usize) -> Self {
+        self.max_pages = max_pages.max(1);
+        self
+    }
+
+    /// The `search_query` sent to the arXiv API.
+    pub fn search_query(&self) -> &str {
+        &self.search_query
+    }
+}
+
+/// Builds the arXiv `search_query` of `config`.
+///
+/// Categories are always part of the query, and followed authors and keywords
+/// narrow it down. A keyword with a `*` ending becomes an arXiv wildcard.
+/// Regular expressions have no arXiv equivalent: they are left out of the
+/// query and only select among the papers it returns.
+fn search_query(config: &EprintConfig) -> Result<String, AppError> {
+    let categories: Vec<String> = config.categories.iter()
+        .map(|category| format!("cat:{}", category))
+        .collect();
+    let mut terms = Vec::new();
+    for keyword in &config.keywords {
+        if keyword.starts_with('-') || keyword.starts_with('/') {
+            continue;
+        }
+        if keyword.ends_with('*') {
+            terms.push(wildcard("all", keyword)?);
+        } else {
+            terms.push(quoted("all", keyword)?);
+        }
+    }
+    for author in &config.authors {
+        terms.push(quoted("au", author)?);
+        for alias in config.aliases.get(author).into_iter().flatten() {
+            terms.push(quoted("au", alias)?);
+        }
+    }
+
+    match (categories.is_empty(), terms.is_empty()) {
+        (true, true) => Err(AppError::ParseError("arXiv search config has no categories, keywords nor authors".to_string())),
+        (false, true) => Ok(any_of(&categories)),
+        (true, false) => Ok(any_of(&terms)),
+        (false, false) => Ok(format!("{} AND {}", any_of(&categories), any_of(&terms))),
+    }
+}
+
+/// `field:"value"` search term. arXiv has no way to escape a `"` inside a phrase.
+fn quoted(field: &str, value: &str) -> Result<String, AppError> {
+    if value.contains('"') {
+        return Err(AppError::ParseError(format!("arXiv search term {} contains a double quote", value)));
+    }
+    Ok(format!("{}:\"{}\"", field, value))
+}
+
+/// `field:value*` search term of a keyword with a `*` ending. arXiv only
+/// expands wildcards outside of quotes, so each word of a phrase is a term of
+/// its own, e.g. `(all:zero AND all:know*)`.
+fn wildcard(field: &str, value: &str) -> Result<String, AppError> {
+    if value.contains(['"', '(', ')']) {
+        return Err(AppError::ParseError(format!("arXiv search term {} contains a quote or a parenthesis", value)));
+    }
+    let words: Vec<String> = value.split_whitespace()
+        .map(|word| format!("{}:{}", field, word))
+        .collect();
+    if words.len() == 1 {
+        Ok(words[0].clone())
+    } else {
+        Ok(format!("({})", words.join(" AND ")))
+    }
+}
+
+fn any_of(terms: &[String]) -> String {
+    if terms.len() == 1 {
+        terms[0].clone()
+    } else {
+        format!("({})", terms.join(" OR "))
+    }
+}
+
+/// `submittedDate` range of a window that ends before today, so that the
+/// search starts at its last day instead of paging back from today.
+fn submitted_within(window: &DateWindow, timezone: Tz) -> Option<String> {
+    let until = window.until?;
+    let first = start_of_day(window.since, timezone);
+    let last = start_of_day(until + Days::new(1), timezone) - TimeDelta::minutes(1);
+    Some(format!("submittedDate:[{} TO {}]", first.format("%Y%m%d%H%M"), last.format("%Y%m%d%H%M")))
+}
+
+/// URL of the page starting at entry `start`, newest submissions first.
+fn query_url(feed_url: &str, search_query: &str, start: usize, page_size: usize) -> Result<String, AppError> {
+    let mut url = Url::parse(feed_url)
+        .map_err(|e| AppError::ParseError(format!("Invalid arXiv URL {}: {}", feed_url, e)))?;
+    url.query_pairs_mut()
+        .append_pair("search_query", search_query)
+        .append_pair("start", &start.to_string())
+        .append_pair("max_results", &page_size.to_string())
+        .append_pair("sortBy", "submittedDate")
+        .append_pair("sortOrder", "descending");
+    Ok(url.to_string())
+}
+
+/// Abstract page of an entry without its version, e.g. `http://arxiv.org/abs/2409.01234`.
+fn abs_url(entry: &Entry) -> String {
+    let url = entry.links().iter()
+        .find(|link| link.rel() == "alternate")
+        .map(|link| link.href())
+        .unwrap_or(entry.id());
+    match url.rfind('v') {
+        Some(v) if v + 1 < url.len() && url[v + 1..].bytes().all(|b| b.is_ascii_digit()) => url[..v].to_string(),
+        _ => url.to_string(),
+    }
+}
+
+/// The message of the entry arXiv answers a rejected query with, such as a
+/// malformed `search_query`, e.g. `<id>http://arxiv.org/api/errors#...</id>`
+/// titled `Error`.
+fn api_error(feed: &Feed) -> Option<String> {
+    match feed.entries() {
+        [entry] if entry.title().value.trim() == "Error" && entry.id().contains("arxiv.org/api/errors") => {
+            Some(one_line(entry.summary().map(|summary| summary.value.as_str()).unwrap_or(entry.id())))
+        }
+        _ => None,
+    }
+}
+
+/// Collapses the line breaks and indentation arXiv leaves in titles and abstracts.
+fn one_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Converts `entry` to a scored article if it is selected by the filter.
+/// Returns an error for entries that cannot be read, which are skipped.
+fn entry_to_article(entry: &Entry, filter: &PaperFilter) -> Result<Option<BlogArticle>, AppError> {
+    let published = entry.published()
+        .ok_or_else(|| AppError::ParseError("Entry has no published date".to_string()))?
+        .with_timezone(&Utc);
+    let updated = entry.updated().with_timezone(&Utc);
+
+    let url = abs_url(entry);
+    let title = one_line(&entry.title().value);
+    let description = one_line(entry.summary().map(|summary| summary.value.as_str()).unwrap_or_default());
+    let subject = entry.categories().iter().map(|category| category.term()).collect::<Vec<_>>().join(", ");
+    let authors: Vec<String> = entry.authors().iter().map(|author| author.name().to_string()).collect();
+
+    let paper = Paper { identifier: &url, title: &title, description: &description, subject: &subject, authors: &authors };
+    let relevance = match filter.score(&paper) {
+        Some(relevance) => relevance,
+        None => return Ok(None),
+    };
+    Ok(Some(BlogArticle {
+        title,
+        url,
+        published,
+        updated: (updated > published).then_some(updated),
+        blog_name: ARXIV_BLOG_NAME.to_string(),
+        authors: (!authors.is_empty()).then(|| format_authors(authors)),
+        matched_rule: Some(relevance.rules.join(", ")),
+        score: Some(relevance.score),
+        summary: None,
+        tags: Vec::new(),
+    }))
+}
+
+#[async_trait]
+impl ArticleFetcher for ArxivFetcher {
+    async fn fetch_articles(&self, ctx: &FetchContext, feed_url: &str, window: &DateWindow, _blog_name: &str, _custom_url_replace: Option<String>) -> Result<Vec<BlogArticle>, AppError> {
+        let mut articles = Vec::new();
+        let search_query = match submitted_within(window, ctx.timezone) {
+            Some(range) => format!("{} AND {}", self.search_query, range),
+            None => self.search_query.clone(),
+        };
+
+        let mut complete = false;
+        for page in 0..self.max_pages {
+            let url = query_url(feed_url, &search_query, page * self.page_size, self.page_size)?;
+            let content = ctx.get_bytes(&url).await?;
+            let feed = Feed::read_from(&content[..])?;
+            if let Some(message) = api_error(&feed) {
+                return Err(AppError::ParseError(format!("arXiv rejected the query: {}", message)));
+            }
+
+            // Entries come newest first, so the first older one ends the search
+            let mut reached_since_date = false;
+            for entry in feed.entries() {
+                let day = entry.published().map(|published| report_date(published.with_timezone(&Utc), ctx.timezone));
+                if day.is_some_and(|day| day < window.since) {
+                    reached_since_date = true;
+                    break;
+                }
+                if day.is_some_and(|day| window.is_after(day)) {
+                    continue;
+                }
+                match entry_to_article(entry, &self.filter) {
+                    Ok(article) => articles.extend(article),
+                    Err(e) => ctx.log.record_skipped(SkippedItem { item: abs_url(entry), reason: e.to_string() }),
+                }
+            }
+
+            if reached_since_date || feed.entries().len() < self.page_size {
+                complete = true;
+                break;
+            }
+        }
+        if !complete {
+            ctx.log.record_warning(format!("Stopped after {} pages, older papers were not fetched", self.max_pages));
+        }
+
+        // Best papers first, keeping the feed order between equal scores
+        articles.sort_by(|a, b| b.score.unwrap_or(0.0).total_cmp(&a.score.unwrap_or(0.0)));
+        Ok(articles)
+    }
+
+    /// Applied by the aggregator, once new papers are told from revisions and profiles are merged.
+    fn top_n(&self) -> Option<usize> {
+        self.filter.top_n()
+    }
+}
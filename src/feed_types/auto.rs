@@ -0,0 +1,243 @@
+use super::{ArticleFetcher, FeedType, FetchContext, FromBlogInfo};
+use crate::models::{BlogArticle, BlogInfo};
+use crate::errors::AppError;
+use crate::dates::DateWindow;
+use crate::http::HttpResponse;
+use async_trait::async_trait;
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use reqwest::header::CONTENT_TYPE;
+use reqwest::Url;
+use scraper::{Html, Selector};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Path of the Substack posts API, relative to the publication's home page.
+const SUBSTACK_API_PATH: &str = "/api/v1/posts/?limit=50";
+
+/// `type` attributes of `<link rel="alternate">` pointing to a feed.
+const FEED_LINK_TYPES: [&str; 4] = ["application/rss+xml", "application/atom+xml", "application/feed+json", "application/json"];
+
+/// A feed found by [`detect_feed`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DetectedFeed {
+    pub feed_type: FeedType,
+    /// URL to fetch with the fetcher of `feed_type`, which may differ from the detected URL.
+    pub feed_url: String,
+    /// Title of the HTML page the feed was discovered from, if any.
+    pub title: Option<String>,
+}
+
+impl DetectedFeed {
+    /// The `blogs.json` entry of this feed, named after the page title or the host.
+    ///
+    /// A `CustomHTML` entry comes with empty selectors to fill in.
+    pub fn blog_info(&self) -> BlogInfo {
+        let name = self.title.clone()
+            .or_else(|| Url::parse(&self.feed_url).ok().and_then(|url| url.host_str().map(str::to_string)))
+            .unwrap_or_else(|| self.feed_url.clone());
+        let custom_selectors = (self.feed_type == FeedType::CustomHTML).then(|| crate::models::CustomSelectors {
+            article_selector: String::new(),
+            article_item_selector: String::new(),
+            title_selector: String::new(),
+            url_selector: String::new(),
+            date_selector: String::new(),
+            date_format: "%B %d, %Y".to_string(),
+        });
+        BlogInfo {
+            name,
+            domain: self.feed_url.clone(),
+            feed_type: self.feed_type.clone(),
+            custom_url_replace: None,
+            custom_selectors,
+            max_attempts: None,
+            eprint_config: None,
+        }
+    }
+}
+
+/// What a response body looks like.
+#[derive(Debug, PartialEq)]
+enum Sniffed {
+    Feed(FeedType),
+    Html,
+}
+
+/// Guesses the kind of document in `body` from its first element, or from
+/// its `content_type` when the body does not tell.
+fn sniff(content_type: &str, body: &[u8]) -> Option<Sniffed> {
+    let text = String::from_utf8_lossy(body);
+    let text = text.trim_start_matches('\u{feff}').trim_start();
+    if text.starts_with('[') {
+        // Substack's posts API answers with a bare array of posts
+        return Some(Sniffed::Feed(FeedType::Substack));
+    }
+    if text.starts_with('{') {
+        let version = serde_json::from_str::<serde_json::Value>(text).ok()
+            .and_then(|json| json["version"].as_str().map(str::to_string));
+        return match version {
+            Some(version) if version.starts_with("https://jsonfeed.org/version/") => Some(Sniffed::Feed(FeedType::JsonFeed)),
+            _ => None,
+        };
+    }
+    if text.starts_with('<') {
+        if let Some(sniffed) = sniff_root_element(text) {
+            return Some(sniffed);
+        }
+    }
+
+    let content_type = content_type.to_ascii_lowercase();
+    if content_type.contains("html") {
+        Some(Sniffed::Html)
+    } else if content_type.contains("rss") {
+        Some(Sniffed::Feed(FeedType::RSS))
+    } else if content_type.contains("atom") {
+        Some(Sniffed::Feed(FeedType::Atom))
+    } else if content_type.contains("feed+json") {
+        Some(Sniffed::Feed(FeedType::JsonFeed))
+    } else {
+        None
+    }
+}
+
+/// Sniffs an XML or HTML document by the name of its root element.
+fn sniff_root_element(text: &str) -> Option<Sniffed> {
+    let mut reader = Reader::from_str(text);
+    loop {
+        let name = match reader.read_event().ok()? {
+            Event::DocType(doctype) if doctype.to_ascii_lowercase().starts_with(b"html") => return Some(Sniffed::Html),
+            Event::Start(element) | Event::Empty(element) => element.local_name().as_ref().to_ascii_lowercase(),
+            Event::Eof => return None,
+            _ => continue,
+        };
+        return match name.as_slice() {
+            b"rss" | b"rdf" => Some(Sniffed::Feed(FeedType::RSS)),
+            b"feed" => Some(Sniffed::Feed(FeedType::Atom)),
+            b"oai-pmh" => Some(Sniffed::Feed(FeedType::Eprint)),
+            b"html" => Some(Sniffed::Html),
+            _ => None,
+        };
+    }
+}
+
+/// Feed sources announced by an HTML page, in document order.
+struct HtmlPage {
+    title: Option<String>,
+    feed_links: Vec<String>,
+    substack: bool,
+}
+
+fn parse_html_page(page_url: &Url, html: &str) -> HtmlPage {
+    let document = Html::parse_document(html);
+    let title_selector = Selector::parse("title").expect("Invalid title selector");
+    let link_selector = Selector::parse(r#"link[rel~="alternate"][href]"#).expect("Invalid link selector");
+    let generator_selector = Selector::parse(r#"meta[name="generator"][content]"#).expect("Invalid generator selector");
+    let title = document.select(&title_selector).next()
+        .map(|title| title.text().collect::<String>().trim().to_string())
+        .filter(|title| !title.is_empty());
+    let feed_links = document.select(&link_selector)
+        .filter(|link| link.value().attr("type").is_some_and(|t| FEED_LINK_TYPES.contains(&t.trim().to_ascii_lowercase().as_str())))
+        .filter_map(|link| link.value().attr("href"))
+        .filter_map(|href| page_url.join(href.trim()).ok())
+        .map(|url| url.to_string())
+        .collect();
+    // Custom domain publications only tell by their generator, any page may embed Substack's CDN
+    let substack = page_url.host_str().is_some_and(|host| host.ends_with(".substack.com"))
+        || document.select(&generator_selector)
+            .filter_map(|meta| meta.value().attr("content"))
+            .any(|generator| generator.to_ascii_lowercase().contains("substack"));
+    HtmlPage { title, feed_links, substack }
+}
+
+async fn fetch_and_sniff(ctx: &FetchContext, url: &str) -> Result<(Option<Sniffed>, HttpResponse), AppError> {
+    let response = ctx.get(url).await?;
+    if !response.status.is_success() {
+        return Err(AppError::HttpError(format!("{} returned {}", url, response.status)));
+    }
+    let content_type = response.headers.get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+    Ok((sniff(content_type, &response.body), response))
+}
+
+/// Finds out which fetcher reads `url`.
+///
+/// Feeds are recognised by their root element (`<rss>`, `<rdf:RDF>`,
+/// `<feed>`, `<OAI-PMH>`), JSON Feeds by their `version` and Substack's posts
+/// API by its JSON array. On an HTML page, the first feed of its
+/// `<link rel="alternate">` elements is used. Substack publications without a
+/// working feed link, known by their `*.substack.com` host or their generator
+/// meta tag, are pointed to their posts API. Other pages without any feed are
+/// reported as [`FeedType::CustomHTML`], which needs selectors to be written
+/// by hand.
+pub async fn detect_feed(ctx: &FetchContext, url: &str) -> Result<DetectedFeed, AppError> {
+    detect(ctx, url).await.map(|(detected, _)| detected)
+}
+
+/// Like [`detect_feed`], also returning the response of `feed_url` when it was
+/// downloaded along the way.
+async fn detect(ctx: &FetchContext, url: &str) -> Result<(DetectedFeed, Option<HttpResponse>), AppError> {
+    let page_url = Url::parse(url)
+        .map_err(|e| AppError::ParseError(format!("Invalid URL {}: {}", url, e)))?;
+    if page_url.host_str() == Some("export.arxiv.org") {
+        return Ok((DetectedFeed { feed_type: FeedType::Arxiv, feed_url: url.to_string(), title: None }, None));
+    }
+
+    let (sniffed, response) = fetch_and_sniff(ctx, url).await?;
+    match sniffed {
+        Some(Sniffed::Feed(feed_type)) => Ok((DetectedFeed { feed_type, feed_url: url.to_string(), title: None }, Some(response))),
+        Some(Sniffed::Html) => {
+            let page = parse_html_page(&page_url, &String::from_utf8_lossy(&response.body));
+            for feed_url in page.feed_links {
+                // A broken link does not hide the next one
+                if let Ok((Some(Sniffed::Feed(feed_type)), feed)) = fetch_and_sniff(ctx, &feed_url).await {
+                    return Ok((DetectedFeed { feed_type, feed_url, title: page.title }, Some(feed)));
+                }
+            }
+            if page.substack {
+                let home = page_url.join("/").map_err(|e| AppError::ParseError(e.to_string()))?;
+                let feed_url = format!("{}{}", home.as_str().trim_end_matches('/'), SUBSTACK_API_PATH);
+                return Ok((DetectedFeed { feed_type: FeedType::Substack, feed_url, title: page.title }, None));
+            }
+            Ok((DetectedFeed { feed_type: FeedType::CustomHTML, feed_url: url.to_string(), title: page.title }, Some(response)))
+        }
+        None => Err(AppError::ParseError(format!("Unrecognised content at {}", url))),
+    }
+}
+
+/// Fetcher of `"feed_type": "Auto"` sources, which detects the feed type on
+/// every run with [`detect_feed`] and hands over to the fetcher that
+/// [`FetchContext::registry`] builds for it.
+///
+/// The detected feed is not downloaded a second time: its response is handed
+/// over through [`FetchContext::prefetched`].
+pub struct AutoFetcher {
+    blog: BlogInfo,
+}
+
+impl FromBlogInfo for AutoFetcher {
+    fn from_blog_info(blog: &BlogInfo) -> Result<Self, AppError> {
+        Ok(AutoFetcher { blog: blog.clone() })
+    }
+}
+
+#[async_trait]
+impl ArticleFetcher for AutoFetcher {
+    async fn fetch_articles(&self, ctx: &FetchContext, feed_url: &str, window: &DateWindow, blog_name: &str, custom_url_replace: Option<String>) -> Result<Vec<BlogArticle>, AppError> {
+        let (detected, response) = detect(ctx, feed_url).await?;
+        if detected.feed_type == FeedType::CustomHTML && self.blog.custom_selectors.is_none() {
+            return Err(AppError::ParseError(format!("No feed found at {}, add it as CustomHTML with selectors", feed_url)));
+        }
+        let blog = BlogInfo {
+            domain: detected.feed_url,
+            feed_type: detected.feed_type,
+            ..self.blog.clone()
+        };
+        let fetcher = ctx.registry.build(&blog)?;
+        let mut ctx = ctx.clone();
+        if let Some(response) = response {
+            ctx.prefetched = Arc::new(HashMap::from([(blog.domain.clone(), response)]));
+        }
+        fetcher.fetch_articles(&ctx, &blog.domain, window, blog_name, custom_url_replace).await
+    }
+}
@@ -1,9 +1,10 @@
-use super::ArticleFetcher;
-use crate::models::BlogArticle;
+use super::{convert_items, ArticleFetcher, FetchContext, FromBlogInfo};
+use crate::models::{BlogArticle, BlogInfo};
 use crate::errors::AppError;
+use crate::dates::{start_of_day, DateWindow};
 use chrono::NaiveDate;
 use async_trait::async_trait;
-use scraper::{Html, Selector};
+use scraper::{ElementRef, Html, Selector};
 use crate::utils::replace_url;
 
 pub struct CustomHtmlFetcher {
@@ -15,10 +16,25 @@ pub struct CustomHtmlFetcher {
     pub date_format: String,
 }
 
+impl FromBlogInfo for CustomHtmlFetcher {
+    fn from_blog_info(blog: &BlogInfo) -> Result<Self, AppError> {
+        let custom_selectors = blog.custom_selectors.as_ref()
+            .ok_or_else(|| AppError::ParseError("Missing custom selectors for CustomHTML".to_string()))?;
+        Ok(CustomHtmlFetcher {
+            article_selector: custom_selectors.article_selector.clone(),
+            article_item_selector: custom_selectors.article_item_selector.clone(),
+            title_selector: custom_selectors.title_selector.clone(),
+            url_selector: custom_selectors.url_selector.clone(),
+            date_selector: custom_selectors.date_selector.clone(),
+            date_format: custom_selectors.date_format.clone(),
+        })
+    }
+}
+
 #[async_trait]
 impl ArticleFetcher for CustomHtmlFetcher {
-    async fn fetch_articles(&self, feed_url: &str, since_date: &NaiveDate, blog_name: &str, custom_url_replace: Option<String>) -> Result<Vec<BlogArticle>, AppError> {
-        let content = reqwest::get(feed_url).await?.text().await?;
+    async fn fetch_articles(&self, ctx: &FetchContext, feed_url: &str, window: &DateWindow, blog_name: &str, custom_url_replace: Option<String>) -> Result<Vec<BlogArticle>, AppError> {
+        let content = ctx.get_text(feed_url).await?;
         let document = Html::parse_document(&content);
 
         let article_selector = Selector::parse(&self.article_selector)
@@ -32,14 +48,18 @@ impl ArticleFetcher for CustomHtmlFetcher {
         let article_item_selector = Selector::parse(&self.article_item_selector)
             .map_err(|e| AppError::ParseError(format!("Invalid article item selector: {:?}", e)))?;
 
-        let mut blog_articles = Vec::new();
-
         let article_wrapper = document.select(&article_selector).next()
             .ok_or_else(|| AppError::ParseError("No article wrapper found".to_string()))?;
 
-        let article_elements = article_wrapper.select(&article_item_selector).collect::<Vec<_>>();
+        let article_elements = article_wrapper.select(&article_item_selector);
 
-        for article in article_elements.iter() {
+        let describe = |position: usize, article: &ElementRef| {
+            article.select(&title_selector).next()
+                .map(|title| title.text().collect::<String>().trim().to_string())
+                .filter(|title| !title.is_empty())
+                .unwrap_or_else(|| format!("item {} of {}", position, feed_url))
+        };
+        convert_items(ctx, article_elements, describe, |article| {
             let title = article.select(&title_selector).next()
                 .ok_or_else(|| AppError::ParseError("Missing title".to_string()))?
                 .text().collect::<String>();
@@ -83,17 +103,18 @@ impl ArticleFetcher for CustomHtmlFetcher {
 
             let date = NaiveDate::parse_from_str(&date_str, &self.date_format)?;
 
-            if date >= *since_date {
-                blog_articles.push(BlogArticle {
-                    title,
-                    url: final_url, // Use the final URL after replacement
-                    date,
-                    blog_name: blog_name.to_string(),
-                    authors: None, // Set authors to None for Custom HTML
-                });
-            }
-        }
-
-        Ok(blog_articles)
+            Ok(window.contains(date).then(|| BlogArticle {
+                title,
+                url: final_url, // Use the final URL after replacement
+                published: start_of_day(date, ctx.timezone),
+                updated: None,
+                blog_name: blog_name.to_string(),
+                authors: None, // Set authors to None for Custom HTML
+                matched_rule: None,
+                score: None,
+                summary: None,
+                tags: Vec::new(),
+            }))
+        })
     }
 }
\ No newline at end of file
@@ -1,16 +1,28 @@
 use chrono::NaiveDate;
+use chrono_tz::Tz;
 use async_trait::async_trait;
-use reqwest::Client;
 use quick_xml::events::Event;
 use quick_xml::Reader;
 use quick_xml::name::QName;  
+use std::collections::HashSet;
+use std::mem;
 use std::str;  
-use super::ArticleFetcher;
-use crate::models::BlogArticle;
+use reqwest::Url;
+use tokio::io::AsyncBufRead;
+use super::{ArticleFetcher, FetchContext, FromBlogInfo};
+use crate::context::SkippedItem;
+use crate::models::{BlogArticle, BlogInfo, EprintConfig};
 use crate::errors::AppError;
-use crate::config::load_eprint_config;
+use crate::dates::{parse_datetime, report_date, start_of_day, DateWindow};
+use crate::filter::{Paper, PaperFilter};
+use crate::config::{load_eprint_config, DEFAULT_EPRINT_CONFIG_FILE};
+use crate::aggregator::EPRINT_BLOG_NAME;
+use crate::utils::format_authors;
 
-#[derive(Debug, Clone)]
+/// Upper bound on followed resumption tokens, in case a server keeps handing out new ones.
+const MAX_PAGES: usize = 200;
+
+#[derive(Debug, Default)]
 struct Record {
     datestamp: String,
     identifier: String,
@@ -19,144 +31,275 @@ struct Record {
     dates: Vec<String>,
     description: String,
     subject: String, 
+    /// First error met while reading the record, which is then skipped.
+    error: Option<AppError>,
 }
 
-pub struct EprintFetcher;
+/// Fetches ePrint papers matching a search config.
+pub struct EprintFetcher {
+    filter: PaperFilter,
+    include_revisions: bool,
+    max_pages: usize,
+}
 
-impl EprintFetcher {
-    fn format_creators(mut creators: Vec<String>) -> String {
-        match creators.len() {
-            1 => creators[0].clone(),
-            2 => format!("{} and {}", creators[0], creators[1]),
-            _ => {
-                let last = if let Some(creator) = creators.pop() {
-                    creator // Remove the last creator safely
-                } else {
-                    return String::new(); // Return an empty string if there are no creators
-                };
-                format!("{} and {}", creators.join(", "), last) // Join the rest with commas and add the last with "and"
-            }
-        }
+impl FromBlogInfo for EprintFetcher {
+    /// Loads the search config from the source's `eprint_config`, or the default file.
+    fn from_blog_info(blog: &BlogInfo) -> Result<Self, AppError> {
+        let path = blog.eprint_config.as_deref().unwrap_or(DEFAULT_EPRINT_CONFIG_FILE);
+        EprintFetcher::new(&load_eprint_config(path)?)
     }
 }
 
-fn should_include_record(record: &Record) -> bool {
-    let config = load_eprint_config().expect("Failed to load eprint config");
-
-    // Check if the record's identifier matches any of the excluded URLs
-    if config.exclude.iter().any(|ex| record.identifier.ends_with(ex)) {
-        return false; // Exclude this record
+impl EprintFetcher {
+    /// Fetcher keeping the papers selected by `config`. Invalid keyword patterns are errors.
+    pub fn new(config: &EprintConfig) -> Result<Self, AppError> {
+        Ok(EprintFetcher {
+            filter: PaperFilter::new(config)?,
+            include_revisions: config.include_revisions,
+            max_pages: MAX_PAGES,
+        })
     }
 
-    // Check if the description, title, or subject contains any of the keywords
-    let description_contains_keyword = config.keywords.iter().any(|keyword| {
-        record.description.to_lowercase().contains(keyword)
-    });
-
-    let title_contains_keyword = config.keywords.iter().any(|keyword| {
-        record.title.to_lowercase().contains(keyword)
-    });
-
-    let subject_contains_keyword = config.keywords.iter().any(|keyword| {
-        record.subject.to_lowercase().contains(keyword)
-    });
+    /// Follows at most `max_pages` pages instead of 200.
+    pub fn with_max_pages(mut self, max_pages: usize) -> Self {
+        self.max_pages = max_pages.max(1);
+        self
+    }
+}
 
-    // Check if any of the authors match
-    let authors_match = record.creators.iter().any(|author| {
-        config.authors.iter().any(|name| author.to_lowercase() == name.as_str().to_lowercase())
-    });
+impl Record {
+    fn paper(&self) -> Paper<'_> {
+        Paper {
+            identifier: &self.identifier,
+            title: &self.title,
+            description: &self.description,
+            subject: &self.subject,
+            authors: &self.creators,
+        }
+    }
+}
 
-    description_contains_keyword || title_contains_keyword || subject_contains_keyword || authors_match
+/// Incremental parser over one `ListRecords` response.
+///
+/// Records are handed out as soon as their closing tag is read, so a page is
+/// never held in memory as a whole.
+struct ListRecordsParser<R> {
+    reader: Reader<R>,
+    buf: Vec<u8>,
+    current_element: Vec<u8>,
+    record: Record,
+    /// Token of the next page; absent or empty on the last page.
+    resumption_token: Option<String>,
+    /// OAI-PMH `<error>` as `(code, message)`.
+    error: Option<(String, String)>,
 }
 
-#[async_trait]
-impl ArticleFetcher for EprintFetcher {
-    async fn fetch_articles(&self, feed_url: &str, since_date: &NaiveDate, _blog_name: &str, _custom_url_replace: Option<String>) -> Result<Vec<BlogArticle>, AppError> {
-        let client = Client::new();
-        let response = client.get(feed_url).send().await?;
-        let xml: String = response.text().await?;
-
-        let mut reader = Reader::from_str(&xml);
-
-        let mut current_element = String::new();
-        let mut records: Vec<Record> = Vec::new();
-        let mut record: Record = Record {
-            datestamp: String::new(),
-            identifier: String::new(),
-            title: String::new(),
-            creators: Vec::new(),
-            dates: Vec::new(),
-            description: String::new(),
-            subject: String::new(), 
-        };
-
-        while let Ok(event) = reader.read_event() {
+impl<R: AsyncBufRead + Unpin> ListRecordsParser<R> {
+    fn new(reader: R) -> Self {
+        ListRecordsParser {
+            reader: Reader::from_reader(reader),
+            buf: Vec::new(),
+            current_element: Vec::new(),
+            record: Record::default(),
+            resumption_token: None,
+            error: None,
+        }
+    }
+
+    /// Reads up to the end of the next record. Returns `None` once the response is exhausted.
+    ///
+    /// Text that cannot be decoded only spoils its record, see [`Record::error`];
+    /// a document that is not well-formed XML is an error.
+    async fn next_record(&mut self) -> Result<Option<Record>, AppError> {
+        loop {
+            self.buf.clear();
+            let event = self.reader.read_event_into_async(&mut self.buf).await
+                .map_err(|e| AppError::ParseError(format!(
+                    "Malformed ListRecords response at byte {}: {}", self.reader.error_position(), e
+                )))?;
             match event {
                 Event::Start(ref e) => {
-                    current_element = str::from_utf8(e.name().as_ref())
-                        .expect("Failed to convert element name to string") // Handle potential UTF-8 conversion error
-                        .to_string(); 
+                    self.current_element.clear();
+                    self.current_element.extend_from_slice(e.name().as_ref());
+                    if self.current_element == b"error" {
+                        let code = e.try_get_attribute("code").ok().flatten()
+                            .and_then(|attr| attr.decode_and_unescape_value(self.reader.decoder()).ok().map(|v| v.into_owned()))
+                            .unwrap_or_default();
+                        self.error = Some((code, String::new()));
+                    }
                 }
                 Event::Text(e) => {
-                    let text = e.unescape()
-                        .expect("Failed to unescape XML text"); // Handle potential unescape error
-                    if !text.trim().is_empty() { // Only assign if text is not empty
-                        match current_element.as_str() {
-                            "dc:identifier" => record.identifier = text.trim().to_string(),
-                            "dc:title" => record.title = text.trim().to_string(),
-                            "dc:creator" => record.creators.push(text.trim().to_string()),
-                            "dc:date" => record.dates.push(text.trim().to_string()), 
-                            "dc:description" => record.description = text.trim().to_string(), 
-                            "dc:subject" => record.subject = text.trim().to_string(), 
-                            "datestamp" => record.datestamp = text.trim().to_string(), 
+                    let text = match e.unescape() {
+                        Ok(text) => text,
+                        Err(e) => {
+                            self.record.error.get_or_insert(AppError::from(e));
+                            continue;
+                        }
+                    };
+                    let text = text.trim();
+                    if !text.is_empty() { // Only assign if text is not empty
+                        let record = &mut self.record;
+                        match self.current_element.as_slice() {
+                            b"dc:identifier" => record.identifier = text.to_string(),
+                            b"dc:title" => record.title = text.to_string(),
+                            b"dc:creator" => record.creators.push(text.to_string()),
+                            b"dc:date" => record.dates.push(text.to_string()),
+                            b"dc:description" => record.description = text.to_string(),
+                            b"dc:subject" => record.subject = text.to_string(),
+                            b"datestamp" => record.datestamp = text.to_string(),
+                            b"resumptionToken" => self.resumption_token = Some(text.to_string()),
+                            b"error" => if let Some((_, message)) = self.error.as_mut() {
+                                *message = text.to_string();
+                            },
                             _ => {}
                         }
                     }
                 }
-                Event::End(ref e) => {
-                    if e.name() == QName(b"record") {
-                        records.push(record.clone()); // Ensure we clone the record
-                        record = Record {
-                            datestamp: String::new(),
-                            identifier: String::new(),
-                            title: String::new(),
-                            creators: Vec::new(),
-                            dates: Vec::new(),
-                            description: String::new(),
-                            subject: String::new(), 
-                        };
-                    }
+                Event::End(ref e) if e.name() == QName(b"record") => {
+                    return Ok(Some(mem::take(&mut self.record)));
                 }
-                Event::Eof => break,  // End of file reached, break the loop
+                Event::Eof => return Ok(None),
                 _ => {}
             }
         }
-        
-        // Convert records to BlogArticle
+    }
+}
+
+/// Converts `record` to a scored article if it was published or, when
+/// revisions are included, revised within `window` and is selected by the filter.
+///
+/// The earliest `dc:date` is the publication time and the latest one up to
+/// the end of the window the time of the last revision, compared to the
+/// window in `timezone`. Returns an error for records that cannot be read,
+/// which are skipped.
+fn record_to_article(record: Record, window: &DateWindow, timezone: Tz, filter: &PaperFilter, include_revisions: bool) -> Result<Option<BlogArticle>, AppError> {
+    if let Some(e) = record.error {
+        return Err(e);
+    }
+    let dates = record.dates.iter()
+        .map(|date| parse_datetime(date, timezone))
+        .collect::<Result<Vec<_>, _>>()?;
+    let date = *dates.iter().min()
+        .ok_or_else(|| AppError::ParseError("Record has no dc:date".to_string()))?;
+    // Versions posted after the window were not out yet
+    let latest = dates.iter().copied()
+        .filter(|version| !window.is_after(report_date(*version, timezone)))
+        .max();
+    let revised_in_window = include_revisions && latest.is_some_and(|latest| window.contains(report_date(latest, timezone)));
+    if !window.contains(report_date(date, timezone)) && !revised_in_window {
+        return Ok(None);
+    }
+    let relevance = match filter.score(&record.paper()) {
+        Some(relevance) => relevance,
+        None => return Ok(None),
+    };
+    let authors = if record.creators.is_empty() {
+        None
+    } else {
+        Some(format_authors(record.creators))
+    };
+    Ok(Some(BlogArticle {
+        title: record.title,
+        url: record.identifier,
+        published: date,
+        updated: latest.filter(|latest| *latest > date),
+        blog_name: EPRINT_BLOG_NAME.to_string(),
+        authors,
+        matched_rule: Some(relevance.rules.join(", ")),
+        score: Some(relevance.score),
+        summary: None,
+        tags: Vec::new(),
+    }))
+}
+
+/// URL of the first `ListRecords` page, restricted to records changed since `from`.
+///
+/// Any `until` of the feed URL is dropped along with its `from`.
+fn list_records_url(feed_url: &str, from: &NaiveDate) -> Result<String, AppError> {
+    let mut url = Url::parse(feed_url)
+        .map_err(|e| AppError::ParseError(format!("Invalid ePrint URL {}: {}", feed_url, e)))?;
+    let pairs: Vec<(String, String)> = url.query_pairs()
+        .filter(|(key, _)| key != "from" && key != "until")
+        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+        .collect();
+    {
+        let mut query = url.query_pairs_mut();
+        query.clear().extend_pairs(pairs);
+        query.append_pair("from", &from.format("%Y-%m-%d").to_string());
+    }
+    Ok(url.to_string())
+}
+
+/// URL of the page following `token`. Per OAI-PMH, the token is the only argument besides the verb.
+fn resumption_url(feed_url: &str, token: &str) -> Result<String, AppError> {
+    let mut url = Url::parse(feed_url)
+        .map_err(|e| AppError::ParseError(format!("Invalid ePrint URL {}: {}", feed_url, e)))?;
+    url.query_pairs_mut()
+        .clear()
+        .append_pair("verb", "ListRecords")
+        .append_pair("resumptionToken", token);
+    Ok(url.to_string())
+}
+
+#[async_trait]
+impl ArticleFetcher for EprintFetcher {
+    async fn fetch_articles(&self, ctx: &FetchContext, feed_url: &str, window: &DateWindow, _blog_name: &str, _custom_url_replace: Option<String>) -> Result<Vec<BlogArticle>, AppError> {
         let mut articles = Vec::new();
-        for record in records {
-            // Check if the dates vector is not empty before accessing it
-            if !record.dates.is_empty() {
-                if let Ok(date) = NaiveDate::parse_from_str(&record.dates[0], "%Y-%m-%dT%H:%M:%SZ") {
-                    if date >= *since_date && should_include_record(&record) {
-                        let creators = record.creators.clone();
-                        let authors = if creators.is_empty() {
-                            None 
-                        } else {
-                            Some(EprintFetcher::format_creators(creators)) 
-                        };
-                        articles.push(BlogArticle {
-                            title: record.title,
-                            url: record.identifier,
-                            date,
-                            blog_name: "Eprint".to_string(), 
-                            authors: authors, 
-                        });
-                    }
+        // OAI-PMH datestamps are in UTC, where the since date may start a day earlier
+        let from = start_of_day(window.since, ctx.timezone).date_naive();
+        // No `until`: a record's datestamp is its last change, so a paper of
+        // the window revised after it would be missed
+        let mut url = list_records_url(feed_url, &from)?;
+        let mut seen_tokens = HashSet::new();
+        let mut complete = false;
+
+        for _ in 0..self.max_pages {
+            let mut page = ListRecordsParser::new(ctx.get_reader(&url).await?);
+            // Records are filtered as they are parsed, so only matches are kept
+            let mut position = 0;
+            while let Some(record) = page.next_record().await? {
+                position += 1;
+                let item = if record.identifier.is_empty() {
+                    format!("record {} of {}", position, url)
+                } else {
+                    record.identifier.clone()
+                };
+                match record_to_article(record, window, ctx.timezone, &self.filter, self.include_revisions) {
+                    Ok(article) => articles.extend(article),
+                    Err(e) => ctx.log.record_skipped(SkippedItem { item, reason: e.to_string() }),
+                }
+            }
+
+            if let Some((code, message)) = page.error {
+                // An empty window is not a failure
+                if code == "noRecordsMatch" {
+                    complete = true;
+                    break;
+                }
+                return Err(AppError::ParseError(format!("OAI-PMH error {}: {}", code, message)));
+            }
+
+            match page.resumption_token {
+                Some(token) if !token.is_empty() && seen_tokens.insert(token.clone()) => {
+                    url = resumption_url(feed_url, &token)?;
+                }
+                _ => {
+                    complete = true;
+                    break;
                 }
             }
         }
+        if !complete {
+            ctx.log.record_warning(format!("Stopped after {} pages, later records were not fetched", self.max_pages));
+        }
 
+        // Best papers first, keeping the feed order between equal scores
+        articles.sort_by(|a, b| b.score.unwrap_or(0.0).total_cmp(&a.score.unwrap_or(0.0)));
         Ok(articles)
     }
+
+    /// Applied by the aggregator, once new papers are told from revisions and profiles are merged.
+    fn top_n(&self) -> Option<usize> {
+        self.filter.top_n()
+    }
 }
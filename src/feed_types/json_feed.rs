@@ -0,0 +1,108 @@
+use super::{convert_items, ArticleFetcher, FetchContext, FromBlogInfo};
+use crate::models::{BlogArticle, BlogInfo};
+use crate::errors::AppError;
+use crate::utils::{format_authors, replace_url};
+use crate::dates::{parse_datetime, report_date, DateWindow};
+use serde::Deserialize;
+use async_trait::async_trait;
+
+/// Fetches the items of a [JSON Feed](https://www.jsonfeed.org/version/1.1/).
+pub struct JsonFeedFetcher;
+
+impl FromBlogInfo for JsonFeedFetcher {
+    fn from_blog_info(_blog: &BlogInfo) -> Result<Self, AppError> {
+        Ok(JsonFeedFetcher)
+    }
+}
+
+/// The parts of a JSON Feed document mapped into articles.
+#[derive(Debug, Deserialize)]
+struct JsonFeed {
+    /// Kept as JSON so that an item that does not fit [`Item`] only spoils itself.
+    items: Vec<serde_json::Value>,
+    /// Authors of every item that names none.
+    #[serde(default)]
+    authors: Vec<Author>,
+    /// JSON Feed 1.0 single author, replaced by `authors` in 1.1.
+    author: Option<Author>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Item {
+    url: Option<String>,
+    external_url: Option<String>,
+    title: Option<String>,
+    summary: Option<String>,
+    date_published: Option<String>,
+    date_modified: Option<String>,
+    #[serde(default)]
+    authors: Vec<Author>,
+    author: Option<Author>,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Author {
+    name: Option<String>,
+}
+
+/// Names of `authors`, or of the 1.0 `author` when there are none.
+fn author_names(authors: &[Author], author: Option<&Author>) -> Vec<String> {
+    let authors: Vec<&Author> = if authors.is_empty() { author.into_iter().collect() } else { authors.iter().collect() };
+    authors.into_iter().filter_map(|author| author.name.clone()).collect()
+}
+
+#[async_trait]
+impl ArticleFetcher for JsonFeedFetcher {
+    async fn fetch_articles(&self, ctx: &FetchContext, feed_url: &str, window: &DateWindow, blog_name: &str, custom_url_replace: Option<String>) -> Result<Vec<BlogArticle>, AppError> {
+        let content = ctx.get_bytes(feed_url).await?;
+        let feed: JsonFeed = serde_json::from_slice(&content)?;
+        let feed_authors = author_names(&feed.authors, feed.author.as_ref());
+
+        let describe = |position: usize, item: &serde_json::Value| {
+            item["url"].as_str()
+                .or(item["external_url"].as_str())
+                .or(item["title"].as_str())
+                .map(str::to_string)
+                .unwrap_or_else(|| format!("item {} of {}", position, feed_url))
+        };
+        convert_items(ctx, feed.items, describe, |item| {
+            let item: Item = serde_json::from_value(item)?;
+            // Title-less items, such as microblog posts, are named by their summary
+            let title = item.title.clone().or_else(|| item.summary.clone())
+                .ok_or_else(|| AppError::ParseError("Missing title".to_string()))?;
+            let link = item.url.as_deref().or(item.external_url.as_deref())
+                .ok_or_else(|| AppError::ParseError("Missing link".to_string()))?;
+            let pub_date = item.date_published.as_deref().or(item.date_modified.as_deref())
+                .ok_or_else(|| AppError::ParseError("Missing publication date".to_string()))?;
+
+            let published = parse_datetime(pub_date, ctx.timezone)?;
+            // A malformed modification date does not cost the item
+            let modified = item.date_modified.as_deref().and_then(|date| parse_datetime(date, ctx.timezone).ok());
+
+            // Handle custom URL replacement
+            let url = replace_url(link, custom_url_replace.as_ref());
+
+            if !window.contains(report_date(published, ctx.timezone)) {
+                return Ok(None);
+            }
+            let mut authors = author_names(&item.authors, item.author.as_ref());
+            if authors.is_empty() {
+                authors = feed_authors.clone();
+            }
+            Ok(Some(BlogArticle {
+                title,
+                url,
+                published,
+                updated: modified.filter(|modified| *modified > published),
+                blog_name: blog_name.to_string(),
+                authors: (!authors.is_empty()).then(|| format_authors(authors)),
+                matched_rule: None,
+                score: None,
+                summary: item.summary,
+                tags: item.tags,
+            }))
+        })
+    }
+}
@@ -3,46 +3,150 @@ mod rss;
 mod atom;
 mod custom_html;
 mod eprint;
+mod arxiv;
+mod json_feed;
+mod auto;
+mod registry;
 
+pub use registry::{FetcherRegistry, FetcherFactory, FromBlogInfo};
 pub use substack::SubstackFetcher;
 pub use rss::RssFetcher;
 pub use atom::AtomFetcher;
 pub use custom_html::CustomHtmlFetcher;
 pub use eprint::EprintFetcher;
+pub use arxiv::ArxivFetcher;
+pub use json_feed::JsonFeedFetcher;
+pub use auto::{detect_feed, AutoFetcher, DetectedFeed};
 
-use chrono::NaiveDate;
+use crate::dates::DateWindow;
 use crate::models::BlogArticle;
 use crate::errors::AppError;
 
+pub use crate::context::FetchContext;
+use crate::context::SkippedItem;
+
 use async_trait::async_trait;
 
 #[async_trait]
 pub trait ArticleFetcher: Send {
-    async fn fetch_articles(&self, feed_url: &str, since_date: &NaiveDate, blog_name: &str, custom_url_replace: Option<String>) -> Result<Vec<BlogArticle>, AppError>;
+    /// Fetches the articles of `feed_url` published within `window`, whose
+    /// days are those of `ctx.timezone`.
+    async fn fetch_articles(&self, ctx: &FetchContext, feed_url: &str, window: &DateWindow, blog_name: &str, custom_url_replace: Option<String>) -> Result<Vec<BlogArticle>, AppError>;
+
+    /// Number of papers of this source to list in each section of the report,
+    /// best first, see [`crate::FetchReport`]. `None` lists them all.
+    fn top_n(&self) -> Option<usize> {
+        None
+    }
+}
+
+/// Converts the items of a feed with `convert`, leaving out the items it fails
+/// on and recording them in the fetch log under the name given by `describe`
+/// (called with the 1-based position of the item).
+///
+/// Fails only when there were items and none of them could be read, which
+/// usually means the source changed its format.
+pub(crate) fn convert_items<T>(
+    ctx: &FetchContext,
+    items: impl IntoIterator<Item = T>,
+    describe: impl Fn(usize, &T) -> String,
+    mut convert: impl FnMut(T) -> Result<Option<BlogArticle>, AppError>,
+) -> Result<Vec<BlogArticle>, AppError> {
+    let mut articles = Vec::new();
+    let mut count = 0;
+    let mut first_error = None;
+    let mut failed = 0;
+
+    for item in items {
+        count += 1;
+        let name = describe(count, &item);
+        match convert(item) {
+            Ok(article) => articles.extend(article),
+            Err(e) => {
+                failed += 1;
+                let reason = e.to_string();
+                first_error.get_or_insert_with(|| reason.clone());
+                ctx.log.record_skipped(SkippedItem { item: name, reason });
+            }
+        }
+    }
+
+    match first_error {
+        Some(reason) if failed == count => {
+            Err(AppError::ParseError(format!("None of the {} items could be read, the first failed with: {}", count, reason)))
+        }
+        _ => Ok(articles),
+    }
 }
 
 use serde::{Serialize, Deserialize};
 
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+/// Kind of source a `blogs.json` entry points to.
+///
+/// Types registered by downstream code in a [`FetcherRegistry`] are carried as `Custom`.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
 pub enum FeedType {
     Substack,
     RSS,
     Atom,
     CustomHTML,
-    Eprint
+    Eprint,
+    Arxiv,
+    JsonFeed,
+    /// Detected on every run, see [`detect_feed`].
+    Auto,
+    Custom(String),
 }
 
+impl FeedType {
+    /// The built-in feed types, all registered in `FetcherRegistry::default()`.
+    pub const BUILTIN: [FeedType; 8] = [FeedType::Substack, FeedType::RSS, FeedType::Atom, FeedType::CustomHTML, FeedType::Eprint, FeedType::Arxiv, FeedType::JsonFeed, FeedType::Auto];
+
+    /// The name used for this type in `blogs.json` and in the [`FetcherRegistry`].
+    pub fn name(&self) -> &str {
+        match self {
+            FeedType::Substack => "Substack",
+            FeedType::RSS => "RSS",
+            FeedType::Atom => "Atom",
+            FeedType::CustomHTML => "CustomHTML",
+            FeedType::Eprint => "Eprint",
+            FeedType::Arxiv => "Arxiv",
+            FeedType::JsonFeed => "JsonFeed",
+            FeedType::Auto => "Auto",
+            FeedType::Custom(name) => name,
+        }
+    }
+}
+
+impl std::fmt::Display for FeedType {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Parses a built-in feed type; unknown names are rejected.
 impl std::str::FromStr for FeedType {
     type Err = AppError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "Substack" => Ok(FeedType::Substack),
-            "RSS" => Ok(FeedType::RSS),
-            "Atom" => Ok(FeedType::Atom),
-            "CustomHTML" => Ok(FeedType::CustomHTML),
-            "Eprint" => Ok(FeedType::Eprint),
-            _ => Err(AppError::UnknownFeedType(s.to_string())),
-        }
+        FeedType::BUILTIN.iter()
+            .find(|feed_type| feed_type.name() == s)
+            .cloned()
+            .ok_or_else(|| AppError::UnknownFeedType(s.to_string()))
     }
-}
\ No newline at end of file
+}
+
+/// Unknown names become [`FeedType::Custom`] and are resolved by the registry.
+impl From<String> for FeedType {
+    fn from(name: String) -> Self {
+        name.parse().unwrap_or(FeedType::Custom(name))
+    }
+}
+
+impl From<FeedType> for String {
+    fn from(feed_type: FeedType) -> Self {
+        feed_type.name().to_string()
+    }
+}
@@ -0,0 +1,77 @@
+use std::collections::HashMap;
+use std::fmt;
+use super::{ArticleFetcher, FeedType, SubstackFetcher, RssFetcher, AtomFetcher, CustomHtmlFetcher, EprintFetcher, ArxivFetcher, JsonFeedFetcher, AutoFetcher};
+use crate::models::BlogInfo;
+use crate::errors::AppError;
+
+/// Builds a fetcher for one `blogs.json` entry.
+pub type FetcherFactory = Box<dyn Fn(&BlogInfo) -> Result<Box<dyn ArticleFetcher>, AppError> + Send + Sync>;
+
+/// Fetchers that can build themselves from their `BlogInfo` entry.
+pub trait FromBlogInfo: ArticleFetcher + Sized + 'static {
+    fn from_blog_info(blog: &BlogInfo) -> Result<Self, AppError>;
+}
+
+/// Maps feed type names to the factories building their fetchers.
+///
+/// `FetcherRegistry::default()` knows every built-in [`FeedType`]. Downstream
+/// code can register additional types and reference them from `blogs.json`
+/// by name, e.g. `"feed_type": "MyFeed"`.
+pub struct FetcherRegistry {
+    factories: HashMap<String, FetcherFactory>,
+}
+
+impl FetcherRegistry {
+    /// Creates a registry without any fetcher.
+    pub fn empty() -> Self {
+        FetcherRegistry { factories: HashMap::new() }
+    }
+
+    /// Registers `factory` under `feed_type`, replacing any previous factory for that name.
+    pub fn register<F>(&mut self, feed_type: &str, factory: F)
+    where
+        F: Fn(&BlogInfo) -> Result<Box<dyn ArticleFetcher>, AppError> + Send + Sync + 'static,
+    {
+        self.factories.insert(feed_type.to_string(), Box::new(factory));
+    }
+
+    /// Registers a fetcher type that builds itself with [`FromBlogInfo`].
+    pub fn register_type<T: FromBlogInfo>(&mut self, feed_type: &str) {
+        self.register(feed_type, |blog| Ok(Box::new(T::from_blog_info(blog)?)));
+    }
+
+    pub fn contains(&self, feed_type: &str) -> bool {
+        self.factories.contains_key(feed_type)
+    }
+
+    /// Builds the fetcher for `blog` from the factory registered under its feed type.
+    pub fn build(&self, blog: &BlogInfo) -> Result<Box<dyn ArticleFetcher>, AppError> {
+        let name = blog.feed_type.name();
+        let factory = self.factories.get(name)
+            .ok_or_else(|| AppError::UnknownFeedType(name.to_string()))?;
+        factory(blog)
+    }
+}
+
+impl fmt::Debug for FetcherRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut feed_types: Vec<&String> = self.factories.keys().collect();
+        feed_types.sort();
+        f.debug_struct("FetcherRegistry").field("feed_types", &feed_types).finish()
+    }
+}
+
+impl Default for FetcherRegistry {
+    fn default() -> Self {
+        let mut registry = FetcherRegistry::empty();
+        registry.register_type::<SubstackFetcher>(FeedType::Substack.name());
+        registry.register_type::<RssFetcher>(FeedType::RSS.name());
+        registry.register_type::<AtomFetcher>(FeedType::Atom.name());
+        registry.register_type::<CustomHtmlFetcher>(FeedType::CustomHTML.name());
+        registry.register_type::<EprintFetcher>(FeedType::Eprint.name());
+        registry.register_type::<ArxivFetcher>(FeedType::Arxiv.name());
+        registry.register_type::<JsonFeedFetcher>(FeedType::JsonFeed.name());
+        registry.register_type::<AutoFetcher>(FeedType::Auto.name());
+        registry
+    }
+}
@@ -1,43 +1,80 @@
-use super::ArticleFetcher;
-use crate::models::BlogArticle;
+use super::{convert_items, ArticleFetcher, FeedType, FetchContext, FromBlogInfo};
+use crate::models::{BlogArticle, BlogInfo};
 use crate::errors::AppError;
-use crate::utils::parse_rss_date;
-use chrono::NaiveDate;
-use rss::Channel;
+use crate::dates::{parse_datetime, report_date, DateWindow};
+use crate::syndication::{parse_feed, FeedEntry};
 use async_trait::async_trait;
-use crate::utils::replace_url;
+use reqwest::Url;
+use crate::utils::{format_authors, replace_url};
 
 pub struct RssFetcher;
 
+impl FromBlogInfo for RssFetcher {
+    fn from_blog_info(_blog: &BlogInfo) -> Result<Self, AppError> {
+        Ok(RssFetcher)
+    }
+}
+
+/// Fetches the entries of any RSS or Atom feed, see [`parse_feed`].
+///
+/// A feed in the other family than `declared` is still read, with a warning
+/// recorded in the fetch log. Entries that cannot be read are skipped, see
+/// [`convert_items`].
+///
+/// An entry is dated by its publication, so that editing an old post does not
+/// bring it back, and only by its last update when that is all it has.
+pub(super) async fn fetch_syndicated_articles(ctx: &FetchContext, feed_url: &str, window: &DateWindow, blog_name: &str, custom_url_replace: Option<String>, declared: FeedType) -> Result<Vec<BlogArticle>, AppError> {
+    let content = ctx.get_bytes(feed_url).await?;
+    let feed = parse_feed(&content)?;
+    let base_url = Url::parse(feed_url).ok();
+    if feed.format.is_atom() != (declared == FeedType::Atom) {
+        ctx.log.record_warning(format!("Declared as {} but is an {} feed", declared, feed.format));
+    }
+
+    let describe = |position: usize, entry: &FeedEntry| {
+        entry.link.clone()
+            .or_else(|| entry.title.clone())
+            .unwrap_or_else(|| format!("item {} of {}", position, feed_url))
+    };
+    let feed_authors = feed.authors;
+    convert_items(ctx, feed.entries, describe, |entry| {
+        let title = entry.title.ok_or_else(|| AppError::ParseError("Missing title".to_string()))?;
+        let link = entry.link.ok_or_else(|| AppError::ParseError("Missing link".to_string()))?;
+        let pub_date = entry.published.clone().or(entry.updated.clone())
+            .ok_or_else(|| AppError::ParseError("Missing publication date".to_string()))?;
+
+        let published = parse_datetime(&pub_date, ctx.timezone)?;
+        // A malformed update time does not cost the entry
+        let updated = entry.updated.filter(|_| entry.published.is_some())
+            .and_then(|updated| parse_datetime(&updated, ctx.timezone).ok())
+            .filter(|updated| *updated > published);
+
+        // Links still relative after xml:base are relative to the feed
+        let link = base_url.as_ref()
+            .and_then(|base| base.join(&link).ok())
+            .map_or(link, String::from);
+        // Handle custom URL replacement
+        let url = replace_url(&link, custom_url_replace.as_ref());
+        let authors = if entry.authors.is_empty() { feed_authors.clone() } else { entry.authors };
+
+        Ok(window.contains(report_date(published, ctx.timezone)).then(|| BlogArticle {
+            title,
+            url,
+            published,
+            updated,
+            blog_name: blog_name.to_string(),
+            authors: (!authors.is_empty()).then(|| format_authors(authors)),
+            matched_rule: None,
+            score: None,
+            summary: None,
+            tags: Vec::new(),
+        }))
+    })
+}
+
 #[async_trait]
 impl ArticleFetcher for RssFetcher {
-    async fn fetch_articles(&self, feed_url: &str, since_date: &NaiveDate, blog_name: &str, custom_url_replace: Option<String>) -> Result<Vec<BlogArticle>, AppError> {
-        let content = reqwest::get(feed_url).await?.bytes().await?;
-        let channel = Channel::read_from(&content[..])?;
-
-        let mut articles = Vec::new();
-
-        for item in channel.items() {
-            let title = item.title().ok_or_else(|| AppError::ParseError("Missing title".to_string()))?;
-            let link = item.link().ok_or_else(|| AppError::ParseError("Missing link".to_string()))?;
-            let pub_date = item.pub_date().ok_or_else(|| AppError::ParseError("Missing publication date".to_string()))?;
-
-            let date = parse_rss_date(pub_date)?;
-
-            // Handle custom URL replacement
-            let url = replace_url(link, custom_url_replace.as_ref());
-
-            if date >= *since_date {
-                articles.push(BlogArticle {
-                    title: title.to_string(),
-                    url,
-                    date,
-                    blog_name: blog_name.to_string(),
-                    authors: None, // Set authors to None for RSS
-                });
-            }
-        }
-
-        Ok(articles)
+    async fn fetch_articles(&self, ctx: &FetchContext, feed_url: &str, window: &DateWindow, blog_name: &str, custom_url_replace: Option<String>) -> Result<Vec<BlogArticle>, AppError> {
+        fetch_syndicated_articles(ctx, feed_url, window, blog_name, custom_url_replace, FeedType::RSS).await
     }
-}
\ No newline at end of file
+}
@@ -1,43 +1,57 @@
-use super::ArticleFetcher;
-use crate::models::BlogArticle;
+use super::{convert_items, ArticleFetcher, FetchContext, FromBlogInfo};
+use crate::models::{BlogArticle, BlogInfo};
 use crate::errors::AppError;
-use chrono::NaiveDate;
+use crate::dates::{parse_datetime, report_date, DateWindow};
 use async_trait::async_trait;
 use crate::utils::replace_url;
 
 pub struct SubstackFetcher;
 
+impl FromBlogInfo for SubstackFetcher {
+    fn from_blog_info(_blog: &BlogInfo) -> Result<Self, AppError> {
+        Ok(SubstackFetcher)
+    }
+}
+
 #[async_trait]
 impl ArticleFetcher for SubstackFetcher {
-    async fn fetch_articles(&self, feed_url: &str, since_date: &NaiveDate, blog_name: &str, custom_url_replace: Option<String>) -> Result<Vec<BlogArticle>, AppError> {
-        let response = reqwest::get(feed_url).await?.text().await?;
+    async fn fetch_articles(&self, ctx: &FetchContext, feed_url: &str, window: &DateWindow, blog_name: &str, custom_url_replace: Option<String>) -> Result<Vec<BlogArticle>, AppError> {
+        let response = ctx.get_text(feed_url).await?;
         let json: serde_json::Value = serde_json::from_str(&response)?;
 
-        let mut articles = Vec::new();
-
-        if let Some(posts) = json.as_array() {
-            for post in posts {
-                let title = post["title"].as_str().ok_or_else(|| AppError::ParseError("Missing title".to_string()))?.to_string();
-                let slug = post["slug"].as_str().ok_or_else(|| AppError::ParseError("Missing slug".to_string()))?;
-                let url = format!("{}/p/{}", feed_url.trim_end_matches("/api/v1/posts/?limit=50"), slug);
-                let date_str = post["post_date"].as_str().ok_or_else(|| AppError::ParseError("Missing post_date".to_string()))?;
-                let date = NaiveDate::parse_from_str(date_str, "%Y-%m-%dT%H:%M:%S%.fZ")?;
+        let posts = match json.as_array() {
+            Some(posts) => posts,
+            None => return Ok(Vec::new()),
+        };
 
-                // Handle custom URL replacement
-                let final_url = replace_url(&url, custom_url_replace.as_ref());
+        let describe = |position: usize, post: &&serde_json::Value| {
+            post["slug"].as_str()
+                .or(post["title"].as_str())
+                .map(str::to_string)
+                .unwrap_or_else(|| format!("post {} of {}", position, feed_url))
+        };
+        convert_items(ctx, posts, describe, |post| {
+            let title = post["title"].as_str().ok_or_else(|| AppError::ParseError("Missing title".to_string()))?.to_string();
+            let slug = post["slug"].as_str().ok_or_else(|| AppError::ParseError("Missing slug".to_string()))?;
+            let url = format!("{}/p/{}", feed_url.trim_end_matches("/api/v1/posts/?limit=50"), slug);
+            let date_str = post["post_date"].as_str().ok_or_else(|| AppError::ParseError("Missing post_date".to_string()))?;
+            let published = parse_datetime(date_str, ctx.timezone)?;
 
-                if date >= *since_date {
-                    articles.push(BlogArticle {
-                        title,
-                        url: final_url,
-                        date,
-                        blog_name: blog_name.to_string(),
-                        authors: None, // Set authors to None for Substack
-                    });
-                }
-            }
-        }
+            // Handle custom URL replacement
+            let final_url = replace_url(&url, custom_url_replace.as_ref());
 
-        Ok(articles)
+            Ok(window.contains(report_date(published, ctx.timezone)).then(|| BlogArticle {
+                title,
+                url: final_url,
+                published,
+                updated: None,
+                blog_name: blog_name.to_string(),
+                authors: None, // Set authors to None for Substack
+                matched_rule: None,
+                score: None,
+                summary: None,
+                tags: Vec::new(),
+            }))
+        })
     }
 }
\ No newline at end of file
@@ -0,0 +1,317 @@
+use chrono::{Local, NaiveDate};
+use regex::{Regex, RegexBuilder};
+use serde::Deserialize;
+use crate::errors::AppError;
+use crate::models::EprintConfig;
+use crate::names::PersonName;
+
+/// One entry of [`EprintConfig::keywords`], matched case-insensitively:
+///
+/// - `stark`: the whole word, so it does not match "starkly"
+/// - `snark*`: words starting with `snark`, such as "SNARKs"
+/// - `zero knowledge`: the words in this order, separated by any whitespace
+/// - `/zk-?snarks?/`: a regular expression
+/// - `-lattice`: any of the above, lowering the score of the papers it matches
+#[derive(Debug, Clone)]
+pub struct KeywordRule {
+    /// The keyword as written in the config, without its `-`.
+    pub keyword: String,
+    pub negative: bool,
+    regex: Regex,
+}
+
+impl KeywordRule {
+    pub fn parse(keyword: &str) -> Result<Self, AppError> {
+        // Without any word left, the rule would match every paper
+        if keyword.trim_matches(|c: char| c == '-' || c == '*' || c.is_whitespace()).is_empty() {
+            return Err(AppError::ParseError(format!("Empty keyword {:?}", keyword)));
+        }
+        let (negative, keyword) = match keyword.strip_prefix('-') {
+            Some(rest) if !rest.is_empty() => (true, rest),
+            _ => (false, keyword),
+        };
+        let pattern = match keyword.strip_prefix('/').and_then(|rest| rest.strip_suffix('/')) {
+            Some("") => return Err(AppError::ParseError(format!("Empty keyword {:?}", keyword))),
+            Some(pattern) => pattern.to_string(),
+            None => word_pattern(keyword),
+        };
+        let regex = RegexBuilder::new(&pattern)
+            .case_insensitive(true)
+            .build()
+            .map_err(|e| AppError::ParseError(format!("Invalid keyword {}: {}", keyword, e)))?;
+        Ok(KeywordRule { keyword: keyword.to_string(), negative, regex })
+    }
+
+    pub fn is_match(&self, text: &str) -> bool {
+        self.regex.is_match(text)
+    }
+}
+
+/// Regex matching `words` as whole words, with a trailing `*` matching any word ending.
+fn word_pattern(words: &str) -> String {
+    let (words, any_ending) = match words.strip_suffix('*') {
+        Some(words) => (words, true),
+        None => (words, false),
+    };
+    let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
+    let phrase = words.split_whitespace().map(regex::escape).collect::<Vec<_>>().join(r"\s+");
+    // `\b` only holds next to a word character, so keywords like `c++` keep their edge as is
+    let start = if words.trim_start().starts_with(is_word_char) { r"\b" } else { "" };
+    let end = if any_ending {
+        r"\w*"
+    } else if words.trim_end().ends_with(is_word_char) {
+        r"\b"
+    } else {
+        ""
+    };
+    format!("{}{}{}", start, phrase, end)
+}
+
+/// One entry of [`EprintConfig::exclude`]: either an identifier suffix such as
+/// `"2024/1613"`, or an object whose fields must all match the paper:
+///
+/// - `identifier`: a suffix of the paper's identifier
+/// - `author`: an author of the paper, compared as in [`PersonName::matches`]
+/// - `subject`: a keyword found in the subject, see [`KeywordRule`]
+/// - `title`: a regular expression found in the title, ignoring case
+/// - `until`: the last day the rule applies, e.g. `"2025-06-30"`
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(from = "ExcludeEntry")]
+pub struct ExcludeRule {
+    pub identifier: Option<String>,
+    pub author: Option<String>,
+    pub subject: Option<String>,
+    pub title: Option<String>,
+    pub until: Option<NaiveDate>,
+}
+
+impl From<String> for ExcludeRule {
+    fn from(identifier: String) -> Self {
+        ExcludeRule { identifier: Some(identifier), ..ExcludeRule::default() }
+    }
+}
+
+/// How an [`ExcludeRule`] may be written in the config.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ExcludeEntry {
+    Identifier(String),
+    Rule {
+        identifier: Option<String>,
+        author: Option<String>,
+        subject: Option<String>,
+        title: Option<String>,
+        until: Option<NaiveDate>,
+    },
+}
+
+impl From<ExcludeEntry> for ExcludeRule {
+    fn from(entry: ExcludeEntry) -> Self {
+        match entry {
+            ExcludeEntry::Identifier(identifier) => ExcludeRule::from(identifier),
+            ExcludeEntry::Rule { identifier, author, subject, title, until } => {
+                ExcludeRule { identifier, author, subject, title, until }
+            }
+        }
+    }
+}
+
+/// Compiled [`ExcludeRule`].
+#[derive(Debug, Clone)]
+struct Exclusion {
+    identifier: Option<String>,
+    author: Option<PersonName>,
+    subject: Option<KeywordRule>,
+    title: Option<Regex>,
+}
+
+impl Exclusion {
+    fn new(rule: &ExcludeRule) -> Result<Self, AppError> {
+        if rule.identifier.is_none() && rule.author.is_none() && rule.subject.is_none() && rule.title.is_none() {
+            return Err(AppError::ParseError("Exclusion rule without identifier, author, subject or title".to_string()));
+        }
+        let author = match &rule.author {
+            Some(author) => Some(PersonName::parse(author)
+                .ok_or_else(|| AppError::ParseError(format!("Invalid excluded author {:?}", author)))?),
+            None => None,
+        };
+        let title = match &rule.title {
+            Some(title) => Some(RegexBuilder::new(title)
+                .case_insensitive(true)
+                .build()
+                .map_err(|e| AppError::ParseError(format!("Invalid excluded title {}: {}", title, e)))?),
+            None => None,
+        };
+        Ok(Exclusion {
+            identifier: rule.identifier.clone(),
+            author,
+            subject: rule.subject.as_deref().map(KeywordRule::parse).transpose()?,
+            title,
+        })
+    }
+
+    fn matches(&self, paper: &Paper, paper_authors: &[PersonName]) -> bool {
+        self.identifier.as_ref().is_none_or(|suffix| paper.identifier.ends_with(suffix.as_str()))
+            && self.author.as_ref().is_none_or(|excluded| paper_authors.iter().any(|author| author.matches(excluded)))
+            && self.subject.as_ref().is_none_or(|rule| rule.is_match(paper.subject))
+            && self.title.as_ref().is_none_or(|regex| regex.is_match(paper.title))
+    }
+}
+
+/// The fields of a paper looked at by a [`PaperFilter`].
+#[derive(Debug, Clone, Copy)]
+pub struct Paper<'a> {
+    pub identifier: &'a str,
+    pub title: &'a str,
+    pub description: &'a str,
+    pub subject: &'a str,
+    pub authors: &'a [String],
+}
+
+/// Weights of the relevance score, read from the `scoring` section of an ePrint search config.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ScoringConfig {
+    /// Added for every keyword found in the title.
+    pub title_weight: f64,
+    /// Added for every keyword found in the abstract.
+    pub abstract_weight: f64,
+    /// Added for every keyword found in the subject.
+    pub subject_weight: f64,
+    /// Added for every followed author, see [`PersonName::matches`].
+    pub author_bonus: f64,
+    /// Taken off for every negative keyword found.
+    pub exclude_penalty: f64,
+    /// Minimum score of a selected paper.
+    pub threshold: f64,
+    /// Maximum number of papers kept by the source, best first.
+    pub top_n: Option<usize>,
+}
+
+impl Default for ScoringConfig {
+    fn default() -> Self {
+        ScoringConfig {
+            title_weight: 3.0,
+            abstract_weight: 1.0,
+            subject_weight: 2.0,
+            author_bonus: 5.0,
+            exclude_penalty: 10.0,
+            threshold: 1.0,
+            top_n: None,
+        }
+    }
+}
+
+/// Why a [`PaperFilter`] selected a paper.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Relevance {
+    pub score: f64,
+    /// Every rule that changed the score, e.g. `keyword "zk" in title`.
+    pub rules: Vec<String>,
+}
+
+/// Compiled [`EprintConfig`], scoring papers and deciding which to include.
+#[derive(Debug, Clone)]
+pub struct PaperFilter {
+    keywords: Vec<KeywordRule>,
+    negative_keywords: Vec<KeywordRule>,
+    authors: Vec<FollowedAuthor>,
+    exclude: Vec<Exclusion>,
+    scoring: ScoringConfig,
+}
+
+/// A followed author and the names they publish under.
+#[derive(Debug, Clone)]
+struct FollowedAuthor {
+    name: String,
+    variants: Vec<PersonName>,
+}
+
+impl PaperFilter {
+    /// Compiles `config`, leaving out exclusion rules that expired before today.
+    /// Invalid regular expressions are errors.
+    pub fn new(config: &EprintConfig) -> Result<Self, AppError> {
+        Self::new_on(config, Local::now().date_naive())
+    }
+
+    /// Compiles `config` as of `today`, see [`PaperFilter::new`].
+    pub fn new_on(config: &EprintConfig, today: NaiveDate) -> Result<Self, AppError> {
+        let (negative_keywords, keywords) = config.keywords.iter()
+            .map(|keyword| KeywordRule::parse(keyword))
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .partition(|rule| rule.negative);
+        Ok(PaperFilter {
+            keywords,
+            negative_keywords,
+            authors: config.authors.iter()
+                .map(|name| FollowedAuthor {
+                    name: name.clone(),
+                    variants: std::iter::once(name)
+                        .chain(config.aliases.get(name).into_iter().flatten())
+                        .filter_map(|variant| PersonName::parse(variant))
+                        .collect(),
+                })
+                .collect(),
+            exclude: config.exclude.iter()
+                .filter(|rule| rule.until.is_none_or(|until| until >= today))
+                .map(Exclusion::new)
+                .collect::<Result<_, _>>()?,
+            scoring: config.scoring.clone(),
+        })
+    }
+
+    /// Maximum number of papers to keep, see [`ScoringConfig::top_n`].
+    pub fn top_n(&self) -> Option<usize> {
+        self.scoring.top_n
+    }
+
+    /// Scores `paper`, returning `None` if an exclusion rule matches it or it scores below the threshold.
+    ///
+    /// Each keyword adds the weight of every field it is found in, each
+    /// followed author adds the author bonus, and each negative keyword
+    /// found anywhere takes off the exclude penalty.
+    pub fn score(&self, paper: &Paper) -> Option<Relevance> {
+        let paper_authors: Vec<PersonName> = paper.authors.iter().filter_map(|author| PersonName::parse(author)).collect();
+        if self.exclude.iter().any(|exclusion| exclusion.matches(paper, &paper_authors)) {
+            return None;
+        }
+
+        let scoring = &self.scoring;
+        let fields = [
+            ("title", paper.title, scoring.title_weight),
+            ("description", paper.description, scoring.abstract_weight),
+            ("subject", paper.subject, scoring.subject_weight),
+        ];
+        let mut relevance = Relevance { score: 0.0, rules: Vec::new() };
+
+        for rule in &self.keywords {
+            for (field, text, weight) in &fields {
+                if rule.is_match(text) {
+                    relevance.score += weight;
+                    relevance.rules.push(format!("keyword \"{}\" in {}", rule.keyword, field));
+                }
+            }
+        }
+
+        for followed in &self.authors {
+            if followed.variants.iter().any(|variant| paper_authors.iter().any(|author| author.matches(variant))) {
+                relevance.score += scoring.author_bonus;
+                relevance.rules.push(format!("author {}", followed.name));
+            }
+        }
+
+        for rule in &self.negative_keywords {
+            if fields.iter().any(|(_, text, _)| rule.is_match(text)) {
+                relevance.score -= scoring.exclude_penalty;
+                relevance.rules.push(format!("negative keyword \"{}\"", rule.keyword));
+            }
+        }
+
+        if relevance.score > 0.0 && relevance.score >= scoring.threshold {
+            Some(relevance)
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+use std::io;
+use std::time::Duration;
+use futures::stream;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::{Client, Proxy, StatusCode};
+use serde::Deserialize;
+use crate::body::StreamingResponse;
+use crate::errors::AppError;
+
+/// HTTP settings shared by every fetcher, read from the `http` section of the settings file.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct HttpConfig {
+    /// Total time allowed for one request, body included.
+    pub timeout_secs: u64,
+    pub connect_timeout_secs: u64,
+    pub user_agent: String,
+    pub max_redirects: usize,
+    /// Responses larger than this are rejected instead of being buffered.
+    pub max_body_bytes: usize,
+    /// Proxy URL used for all requests, e.g. `http://proxy.local:3128`.
+    pub proxy: Option<String>,
+    pub default_headers: HashMap<String, String>,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        HttpConfig {
+            timeout_secs: 30,
+            connect_timeout_secs: 10,
+            user_agent: format!("zkmesh_fetcher/{}", env!("CARGO_PKG_VERSION")),
+            max_redirects: 10,
+            max_body_bytes: 32 * 1024 * 1024,
+            proxy: None,
+            default_headers: HashMap::new(),
+        }
+    }
+}
+
+/// A buffered HTTP response.
+#[derive(Debug, Clone)]
+pub struct HttpResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    /// Returns the body of a successful response, or an error naming the status.
+    pub fn into_body(self, url: &str) -> Result<Vec<u8>, AppError> {
+        if self.status.is_success() {
+            Ok(self.body)
+        } else {
+            Err(AppError::HttpError(format!("{} returned {}", url, self.status)))
+        }
+    }
+}
+
+/// HTTP client used by all fetchers. Cloning is cheap and shares the connection pool.
+#[derive(Debug, Clone)]
+pub struct HttpClient {
+    client: Client,
+    max_body_bytes: usize,
+}
+
+impl HttpClient {
+    pub fn new(config: &HttpConfig) -> Result<Self, AppError> {
+        let mut headers = HeaderMap::new();
+        for (name, value) in &config.default_headers {
+            let name = HeaderName::from_bytes(name.as_bytes())
+                .map_err(|e| AppError::HttpError(format!("Invalid header name {}: {}", name, e)))?;
+            let value = HeaderValue::from_str(value)
+                .map_err(|e| AppError::HttpError(format!("Invalid value for header {}: {}", name, e)))?;
+            headers.insert(name, value);
+        }
+
+        let mut builder = Client::builder()
+            .user_agent(config.user_agent.clone())
+            .timeout(Duration::from_secs(config.timeout_secs))
+            .connect_timeout(Duration::from_secs(config.connect_timeout_secs))
+            .redirect(reqwest::redirect::Policy::limited(config.max_redirects))
+            .default_headers(headers);
+        if let Some(proxy) = &config.proxy {
+            builder = builder.proxy(Proxy::all(proxy)?);
+        }
+
+        Ok(HttpClient {
+            client: builder.build()?,
+            max_body_bytes: config.max_body_bytes,
+        })
+    }
+
+    /// Sends a single GET request to `url` and buffers the response, whatever its status.
+    ///
+    /// Bodies larger than `max_body_bytes` are errors.
+    pub async fn get(&self, url: &str) -> Result<HttpResponse, AppError> {
+        self.get_with_headers(url, HeaderMap::new()).await
+    }
+
+    /// Like [`HttpClient::get`], sending `headers` on top of the default headers.
+    pub async fn get_with_headers(&self, url: &str, headers: HeaderMap) -> Result<HttpResponse, AppError> {
+        self.open(url, headers).await?.buffer().await
+    }
+
+    /// Sends a single GET request to `url` and returns as soon as the headers arrive.
+    ///
+    /// The body is read as it is consumed; reading past `max_body_bytes` fails.
+    pub async fn open(&self, url: &str, headers: HeaderMap) -> Result<StreamingResponse, AppError> {
+        let response = self.client.get(url).headers(headers).send().await?;
+
+        if let Some(length) = response.content_length() {
+            if length > self.max_body_bytes as u64 {
+                return Err(AppError::HttpError(self.body_too_large(url)));
+            }
+        }
+
+        let status = response.status();
+        let headers = response.headers().clone();
+        let limit = self.max_body_bytes;
+        let too_large = self.body_too_large(url);
+        let body = stream::unfold(Some((response, 0)), move |state| {
+            let too_large = too_large.clone();
+            async move {
+                let (mut response, read) = state?;
+                match response.chunk().await {
+                    Ok(Some(chunk)) if read + chunk.len() > limit => {
+                        Some((Err(io::Error::other(too_large)), None))
+                    }
+                    Ok(Some(chunk)) => {
+                        let read = read + chunk.len();
+                        Some((Ok(chunk), Some((response, read))))
+                    }
+                    Ok(None) => None,
+                    Err(e) => Some((Err(io::Error::other(e)), None)),
+                }
+            }
+        });
+        Ok(StreamingResponse { status, headers, body: Box::pin(body) })
+    }
+
+    /// Fetches `url` and returns the response body. Non-success statuses are errors.
+    pub async fn get_bytes(&self, url: &str) -> Result<Vec<u8>, AppError> {
+        self.get(url).await?.into_body(url)
+    }
+
+    /// Fetches `url` and returns the response body as text, replacing invalid UTF-8.
+    pub async fn get_text(&self, url: &str) -> Result<String, AppError> {
+        let body = self.get_bytes(url).await?;
+        Ok(String::from_utf8_lossy(&body).into_owned())
+    }
+
+    fn body_too_large(&self, url: &str) -> String {
+        format!("Response from {} exceeds {} bytes", url, self.max_body_bytes)
+    }
+}
+
+impl Default for HttpClient {
+    fn default() -> Self {
+        HttpClient::new(&HttpConfig::default()).expect("Failed to build the default HTTP client")
+    }
+}
@@ -0,0 +1,51 @@
+//! zkMesh Fetcher library.
+//!
+//! Fetches and aggregates articles from Substack pages, RSS, Atom and JSON
+//! feeds, custom HTML pages, ePrint and arXiv. The [`Aggregator`] is the main
+//! entry point: give it a list of [`BlogInfo`] entries and it returns the
+//! fetched articles together with the errors encountered for each source.
+//!
+//! ```no_run
+//! use chrono::NaiveDate;
+//! use zkmesh_fetcher::{config, Aggregator, DateWindow};
+//!
+//! # async fn run() -> Result<(), zkmesh_fetcher::AppError> {
+//! let blogs = config::read_blogs_from_file("./config/blogs.json")?;
+//! let window = DateWindow::since(NaiveDate::from_ymd_opt(2024, 9, 1).unwrap());
+//! let report = Aggregator::new(blogs).fetch(&window).await;
+//! for article in report.other_articles {
+//!     println!("{} ({})", article.title, article.url);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+#[cfg(test)]
+mod tests;
+pub mod aggregator;
+pub mod body;
+pub mod cache;
+pub mod config;
+pub mod context;
+pub mod dates;
+pub mod errors;
+pub mod feed_types;
+pub mod filter;
+pub mod http;
+pub mod models;
+pub mod names;
+pub mod output;
+pub mod rate_limit;
+pub mod replay;
+pub mod retry;
+pub mod syndication;
+pub mod utils;
+
+pub use aggregator::{Aggregator, FetchReport};
+pub use errors::AppError;
+pub use context::FetchContext;
+pub use dates::DateWindow;
+pub use feed_types::{ArticleFetcher, FeedType};
+pub use http::{HttpClient, HttpConfig};
+pub use models::{BlogArticle, BlogInfo};
+pub use retry::RetryPolicy;
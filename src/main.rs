@@ -1,192 +1,49 @@
-#[cfg(test)]
-mod tests;
-mod feed_types;
-mod errors;
-mod utils;
-mod config;
-mod models;
-
-use chrono::NaiveDate;
-use tokio;
-use feed_types::{FeedType, ArticleFetcher, SubstackFetcher, RssFetcher, AtomFetcher, CustomHtmlFetcher, EprintFetcher};
-use errors::AppError;
-use crate::models::{BlogInfo, BlogArticle};
-use crate::utils::{capitalize_title, parse_args, write_output};
+use std::path::Path;
+use zkmesh_fetcher::{config, Aggregator, AppError, FeedType, FetchContext, HttpClient};
+use zkmesh_fetcher::cache::HttpCache;
+use zkmesh_fetcher::feed_types::detect_feed;
+use zkmesh_fetcher::models::Settings;
+use zkmesh_fetcher::replay::{FixtureMode, FixtureStore};
+use zkmesh_fetcher::output::generate_html_output;
+use zkmesh_fetcher::utils::{parse_args, write_output, DEFAULT_OUTPUT_FILE, DEFAULT_SETTINGS_FILE};
 
 #[tokio::main]
 async fn main() -> Result<(), AppError> {
-    let (blogs_file, since_date) = parse_args()?;
-    let blogs = config::read_blogs_from_file(&blogs_file)?;
-    let (eprint_articles, other_articles, errors) = fetch_articles(&blogs, &since_date).await?;
-    let html_output = generate_html_output(eprint_articles, other_articles, errors, since_date, &blogs).await?; 
-    write_output(&html_output)?;
-    Ok(())
-}
-
-async fn fetch_articles(blogs: &[BlogInfo], since_date: &NaiveDate) -> Result<(Vec<BlogArticle>, Vec<BlogArticle>, Vec<(String, String)>), AppError> {
-    let mut tasks = Vec::new();
-    let mut errors = Vec::new();
-
-    for blog in blogs {
-        let fetcher: Box<dyn ArticleFetcher> = match blog.feed_type {
-            FeedType::Substack => Box::new(SubstackFetcher),
-            FeedType::RSS => Box::new(RssFetcher),
-            FeedType::Atom => Box::new(AtomFetcher),
-            FeedType::CustomHTML => {
-                let custom_selectors = blog.custom_selectors.as_ref()
-                    .ok_or_else(|| AppError::ParseError("Missing custom selectors for CustomHTML".to_string()))?;
-                Box::new(CustomHtmlFetcher {
-                    article_selector: custom_selectors.article_selector.clone(),
-                    article_item_selector: custom_selectors.article_item_selector.clone(),
-                    title_selector: custom_selectors.title_selector.clone(),
-                    url_selector: custom_selectors.url_selector.clone(),
-                    date_selector: custom_selectors.date_selector.clone(),
-                    date_format: custom_selectors.date_format.clone(),
-                })
-            },
-            FeedType::Eprint => Box::new(EprintFetcher),
-        };
-
-        let blog_clone = blog.clone();
-        let since_date_clone = since_date.clone(); 
-        let custom_url_replace = blog.custom_url_replace.clone(); 
-        let task = tokio::spawn(async move {
-            fetcher.fetch_articles(&blog_clone.domain, &since_date_clone, &blog_clone.name, custom_url_replace).await
-        });
-        tasks.push((task, blog.name.clone()));
-    }
-
-    let mut eprint_articles = Vec::new();
-    let mut other_articles = Vec::new();
-
-    for (task, blog_name) in tasks {
-        match task.await {
-            Ok(Ok(articles)) => {
-                for article in articles {
-                    if article.blog_name == "Eprint" {
-                        eprint_articles.push(article);
-                    } else {
-                        other_articles.push(article);
-                    }
-                }
-            }
-            Ok(Err(e)) => {
-                errors.push((blog_name, e.to_string()));
-            }
-            Err(e) => {
-                errors.push((blog_name, e.to_string()));
-            }
-        }
-    }
-
-    // Sort articles by date
-    eprint_articles.sort_by(|a, b| a.date.cmp(&b.date));
-    other_articles.sort_by(|a, b| a.date.cmp(&b.date));
-
-    Ok((eprint_articles, other_articles, errors))
-}
-
-async fn generate_html_output(
-    eprint_articles: Vec<BlogArticle>, 
-    other_articles: Vec<BlogArticle>, 
-    errors: Vec<(String, String)>, 
-    since_date: NaiveDate, 
-    blogs: &[BlogInfo] 
-) -> Result<String, AppError> {
-    let mut html_output = String::from("<html><body>");
-
-    // Add Eprint articles
-    if !eprint_articles.is_empty() {
-        html_output.push_str("<h2>ePrint Papers</h2><ul>");
-        for article in eprint_articles {
-            let authors_or_blog_name = article.authors.clone().unwrap_or_else(|| "Unknown Author".to_string());
-            let capitalized_title = capitalize_title(&article.title);
-            html_output.push_str(&format!("<li><a href=\"{}\">{}</a> | {}</li>", article.url, capitalized_title, authors_or_blog_name));
-        }
-        html_output.push_str("</ul>");
-    }
-
-    // Add other articles
-    if !other_articles.is_empty() {
-        html_output.push_str("<h2>Blog Articles</h2><ul>");
-        for article in other_articles {
-            let authors_or_blog_name = article.blog_name.clone();
-            let capitalized_title = capitalize_title(&article.title);
-            html_output.push_str(&format!("<li><a href=\"{}\">{}</a> | {}</li>", article.url, capitalized_title, authors_or_blog_name));
+    let args = parse_args()?;
+    let settings = match &args.settings_file {
+        Some(settings_file) => config::read_settings_from_file(settings_file)?,
+        None if Path::new(DEFAULT_SETTINGS_FILE).exists() => config::read_settings_from_file(DEFAULT_SETTINGS_FILE)?,
+        None => Settings::default(),
+    };
+    if let Some(url) = &args.detect_url {
+        let detected = detect_feed(&FetchContext::new(HttpClient::new(&settings.http)?), url).await?;
+        if detected.feed_type == FeedType::CustomHTML {
+            eprintln!("No feed found at {}, fill in the selectors of this CustomHTML entry:", url);
         }
-        html_output.push_str("</ul>");
-    }
-
-    // Add fetching information
-    html_output.push_str(&format!("<h2>Fetching Info</h2>"));
-    html_output.push_str(&format!("<h3>Date Threshold:</h3>"));
-    html_output.push_str(&format!("<p>{}</p>", since_date));
-    html_output.push_str(&format!("<h3>Generation Date/Time:</h3>"));
-    let run_date = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
-    html_output.push_str(&format!("<p>{}</p>", run_date));
-
-    // Add list of blogs
-    html_output.push_str("<h3>List of Sources:</h3><ul>");
-    for blog in blogs { 
-        html_output.push_str(&format!("<li><a href=\"{}\">{}</a></li>", blog.domain, blog.name));
-    }
-    html_output.push_str("</ul>");
-
-    // Add errors if any
-    if !errors.is_empty() {
-        html_output.push_str("<h3>Errors:</h3><ul>");
-        for (blog_name, error) in errors {
-            html_output.push_str(&format!("<li><strong>{}</strong>: {}</li>", blog_name, error));
-        }
-        html_output.push_str("</ul>");
-    }
-
-    html_output.push_str("</body></html>");
-    Ok(html_output)
-}
-
-#[cfg(test)]
-pub async fn run_with_args(args: Vec<String>) -> Result<(), AppError> {
-    let blogs_file = args.get(1).ok_or_else(|| AppError::ParseError("Missing blogs file argument".to_string()))?;
-    let since_date_str = args.get(2).ok_or_else(|| AppError::ParseError("Missing since date argument".to_string()))?;
-
-    let blogs = config::read_blogs_from_file(blogs_file)?;
-    let since_date = NaiveDate::parse_from_str(since_date_str, "%Y-%m-%d")?;
-
-    let mut tasks = Vec::new();
-
-    for blog in blogs {
-        let fetcher: Box<dyn ArticleFetcher> = match blog.feed_type {
-            FeedType::Substack => Box::new(SubstackFetcher),
-            FeedType::RSS => Box::new(RssFetcher),
-            FeedType::Atom => Box::new(AtomFetcher),
-            FeedType::CustomHTML => {
-                let custom_selectors = blog.custom_selectors.as_ref()
-                    .ok_or_else(|| AppError::ParseError("Missing custom selectors for CustomHTML".to_string()))?;
-                Box::new(CustomHtmlFetcher {
-                    article_selector: custom_selectors.article_selector.clone(),
-                    article_item_selector: custom_selectors.article_item_selector.clone(),
-                    title_selector: custom_selectors.title_selector.clone(),
-                    url_selector: custom_selectors.url_selector.clone(),
-                    date_selector: custom_selectors.date_selector.clone(),
-                    date_format: custom_selectors.date_format.clone(),
-                })
-            },
-            FeedType::Eprint => Box::new(EprintFetcher),
-        };
-
-        let task = tokio::spawn(async move {
-            fetcher.fetch_articles(&blog.domain, &since_date, &blog.name, blog.custom_url_replace).await
-        });
-        tasks.push(task);
+        println!("{}", serde_json::to_string_pretty(&detected.blog_info())?);
+        return Ok(());
     }
-
-    for task in tasks {
-        let articles = task.await??;
-        for article in articles {
-            println!("[{}]({}) | {} ({})", article.title, article.url, article.blog_name, article.date);
+    let mut blogs = config::read_blogs_from_file(&args.blogs_file)?;
+    if let Some(eprint_config) = &args.eprint_config {
+        for blog in blogs.iter_mut().filter(|blog| matches!(blog.feed_type, FeedType::Eprint | FeedType::Arxiv)) {
+            blog.eprint_config.get_or_insert_with(|| eprint_config.clone());
         }
     }
-
+    let mut aggregator = Aggregator::new(blogs)
+        .with_http_client(HttpClient::new(&settings.http)?)
+        .with_retry_policy(settings.retry.clone())
+        .with_rate_limit(&settings.rate_limit)
+        .with_timezone(settings.timezone);
+    if settings.cache.enabled {
+        aggregator = aggregator.with_cache(HttpCache::new(&settings.cache.dir));
+    }
+    if let Some(record_dir) = &args.record_dir {
+        aggregator = aggregator.with_fixtures(FixtureMode::Record(FixtureStore::new(record_dir)));
+    } else if let Some(replay_dir) = &args.replay_dir {
+        aggregator = aggregator.with_fixtures(FixtureMode::Replay(FixtureStore::new(replay_dir)));
+    }
+    let report = aggregator.fetch(&args.window).await;
+    let html_output = generate_html_output(report, args.window, aggregator.blogs());
+    write_output(Path::new(DEFAULT_OUTPUT_FILE), &html_output)?;
     Ok(())
-}
\ No newline at end of file
+}
@@ -1,5 +1,12 @@
+use crate::cache::CacheConfig;
 use crate::feed_types::FeedType;
-use chrono::NaiveDate;
+use crate::filter::{ExcludeRule, ScoringConfig};
+use crate::http::HttpConfig;
+use crate::rate_limit::RateLimitConfig;
+use crate::retry::RetryPolicy;
+use std::collections::HashMap;
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
 use serde::{Serialize, Deserialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -7,8 +14,16 @@ pub struct BlogInfo {
     pub name: String,
     pub domain: String,
     pub feed_type: FeedType,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub custom_url_replace: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub custom_selectors: Option<CustomSelectors>,
+    /// Overrides `retry.max_attempts` from the settings for this source.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_attempts: Option<u32>,
+    /// Search config file of an ePrint or arXiv source, see [`EprintConfig`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub eprint_config: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -25,14 +40,50 @@ pub struct CustomSelectors {
 pub struct BlogArticle {
     pub title: String,
     pub url: String,
-    pub date: NaiveDate,
+    /// Publication time; sources giving only a day are read as midnight in the reporting timezone.
+    pub published: DateTime<Utc>,
+    /// Time of the latest revision, when the source reports one after `published`.
+    pub updated: Option<DateTime<Utc>>,
     pub blog_name: String,
     pub authors: Option<String>,
+    /// Filter rules that selected the article, e.g. `keyword "zk" in title`.
+    pub matched_rule: Option<String>,
+    /// Relevance score of a filtered article, see [`ScoringConfig`].
+    pub score: Option<f64>,
+    /// Short description given by the source.
+    pub summary: Option<String>,
+    /// Tags or categories given by the source.
+    pub tags: Vec<String>,
 }
 
-#[derive(Debug, Deserialize)]
+/// Keyword profile of an ePrint or arXiv source, read from its `eprint_config` file.
+///
+/// See [`KeywordRule`](crate::filter::KeywordRule) for the syntax of `keywords`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
 pub struct EprintConfig {
     pub keywords: Vec<String>,
     pub authors: Vec<String>,
-    pub exclude: Vec<String>,
-}
\ No newline at end of file
+    /// Other spellings of followed authors, e.g. `{"Benedikt Bünz": ["Benedikt Buenz"]}`.
+    pub aliases: HashMap<String, Vec<String>>,
+    /// Papers never selected, see [`ExcludeRule`].
+    pub exclude: Vec<ExcludeRule>,
+    pub scoring: ScoringConfig,
+    /// Whether papers published before the since date but revised after it are kept.
+    pub include_revisions: bool,
+    /// arXiv categories searched by arXiv sources, e.g. `cs.CR`; ignored by ePrint.
+    pub categories: Vec<String>,
+}
+
+/// Run-wide settings, read from `./config/settings.json` or the file given with `--settings`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub http: HttpConfig,
+    pub retry: RetryPolicy,
+    pub rate_limit: RateLimitConfig,
+    pub cache: CacheConfig,
+    /// Timezone of the report, e.g. `Europe/Paris`, in which entry dates are
+    /// compared to the since date. Defaults to UTC.
+    pub timezone: Tz,
+}
@@ -0,0 +1,101 @@
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+/// A person name reduced to what author matching compares.
+///
+/// Names are folded to lowercase ASCII-like text ("Bünz" becomes `bunz`) and
+/// may be written "First Middle Last", "F. Last" or "Last, First". Lowercase
+/// particles belong to the family name in both forms, so "Jan van der Berg"
+/// and "Berg, Jan van der" give `van der berg`, and suffixes such as "Jr." or
+/// "III" are left out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PersonName {
+    /// Folded family name, e.g. `ben-sasson` or `van der berg`.
+    pub family: String,
+    /// Folded given names in order, initials as single letters.
+    pub given: Vec<String>,
+}
+
+impl PersonName {
+    /// Parses `name`, returning `None` if it holds no name at all.
+    pub fn parse(name: &str) -> Option<Self> {
+        // A part holding only a suffix, as in "Boneh, Dan, Jr.", is dropped
+        let parts: Vec<Vec<&str>> = name.split(',')
+            .map(words)
+            .filter(|part| !part.is_empty())
+            .collect();
+        let (mut family, mut given) = match parts.as_slice() {
+            [] => return None,
+            [name] => {
+                let mut given = name.clone();
+                let family = vec![given.pop()?];
+                (family, given)
+            }
+            [family, given, ..] => (family.clone(), given.clone()),
+        };
+        while given.last().is_some_and(|word| is_particle(word)) {
+            family.insert(0, given.pop()?);
+        }
+        Some(PersonName {
+            family: family.iter().map(|word| fold(word)).collect::<Vec<_>>().join(" "),
+            given: given.iter().map(|word| fold(word)).collect(),
+        })
+    }
+
+    /// Whether both names may belong to the same person.
+    ///
+    /// Family names must be equal and first given names compatible: an
+    /// initial matches any name starting with it. Middle names are ignored,
+    /// so "Benjamin E. Diamond" matches "Benjamin Diamond".
+    pub fn matches(&self, other: &PersonName) -> bool {
+        if self.family != other.family {
+            return false;
+        }
+        match (self.given.first(), other.given.first()) {
+            (Some(a), Some(b)) if is_initial(a) || is_initial(b) => a.chars().next() == b.chars().next(),
+            (Some(a), Some(b)) => a == b,
+            (None, None) => true,
+            _ => false,
+        }
+    }
+}
+
+fn is_initial(name: &str) -> bool {
+    name.chars().count() == 1
+}
+
+/// Lowercase words joining the family name, as in "Ludwig van Beethoven".
+const PARTICLES: [&str; 17] = ["van", "von", "der", "den", "de", "del", "della", "di", "da", "das", "dos", "du", "la", "le", "ten", "ter", "zu"];
+
+/// Generational suffixes, which are not compared.
+const SUFFIXES: [&str; 5] = ["jr", "sr", "ii", "iii", "iv"];
+
+/// Capitalised particles are left to the given names, e.g. the middle name of "Tran Van Long".
+fn is_particle(word: &str) -> bool {
+    !word.chars().any(char::is_uppercase) && PARTICLES.contains(&fold(word).as_str())
+}
+
+/// Words of `text` without their dots, leaving out suffixes.
+fn words(text: &str) -> Vec<&str> {
+    text.split(|c: char| c.is_whitespace() || c == '.')
+        .filter(|word| !word.is_empty() && !SUFFIXES.contains(&fold(word).as_str()))
+        .collect()
+}
+
+/// Lowercases `text` and strips diacritics, including letters that do not decompose such as `ø` or `ł`.
+fn fold(text: &str) -> String {
+    let mut folded = String::with_capacity(text.len());
+    for c in text.nfd().filter(|c| !is_combining_mark(*c)) {
+        match c {
+            'ø' | 'Ø' => folded.push('o'),
+            'ł' | 'Ł' => folded.push('l'),
+            'đ' | 'Đ' => folded.push('d'),
+            'ı' => folded.push('i'),
+            'ß' => folded.push_str("ss"),
+            'æ' | 'Æ' => folded.push_str("ae"),
+            'œ' | 'Œ' => folded.push_str("oe"),
+            c => folded.extend(c.to_lowercase()),
+        }
+    }
+    folded
+}
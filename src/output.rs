@@ -0,0 +1,124 @@
+use chrono_tz::Tz;
+use crate::aggregator::FetchReport;
+use crate::dates::{report_date, DateWindow};
+use crate::models::{BlogArticle, BlogInfo};
+use crate::utils::capitalize_title;
+
+/// Renders a fetch report as the HTML page published in `./output/index.html`.
+pub fn generate_html_output(report: FetchReport, window: DateWindow, blogs: &[BlogInfo]) -> String {
+    let mut html_output = String::from("<html><body>");
+
+    // Add Eprint articles
+    if !report.eprint_articles.is_empty() {
+        html_output.push_str("<h2>ePrint Papers</h2><ul>");
+        for article in report.eprint_articles {
+            html_output.push_str(&paper_item(article, report.timezone));
+        }
+        html_output.push_str("</ul>");
+    }
+
+    // Add Eprint papers revised since the date threshold
+    if !report.eprint_revisions.is_empty() {
+        html_output.push_str("<h2>Updated Papers</h2><ul>");
+        for article in report.eprint_revisions {
+            html_output.push_str(&paper_item(article, report.timezone));
+        }
+        html_output.push_str("</ul>");
+    }
+
+    // Add arXiv articles
+    if !report.arxiv_articles.is_empty() {
+        html_output.push_str("<h2>arXiv Papers</h2><ul>");
+        for article in report.arxiv_articles {
+            html_output.push_str(&paper_item(article, report.timezone));
+        }
+        html_output.push_str("</ul>");
+    }
+
+    // Add other articles
+    if !report.other_articles.is_empty() {
+        html_output.push_str("<h2>Blog Articles</h2><ul>");
+        for article in report.other_articles {
+            let authors_or_blog_name = article.blog_name.clone();
+            let capitalized_title = capitalize_title(&article.title);
+            html_output.push_str(&format!("<li><a href=\"{}\">{}</a> | {}</li>", article.url, capitalized_title, authors_or_blog_name));
+        }
+        html_output.push_str("</ul>");
+    }
+
+    // Add fetching information
+    html_output.push_str("<h2>Fetching Info</h2>");
+    html_output.push_str("<h3>Date Window:</h3>");
+    html_output.push_str(&format!("<p>{}</p>", window));
+    html_output.push_str("<h3>Generation Date/Time:</h3>");
+    let run_date = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    html_output.push_str(&format!("<p>{}</p>", run_date));
+
+    // Add list of blogs
+    html_output.push_str("<h3>List of Sources:</h3><ul>");
+    for blog in blogs {
+        html_output.push_str(&format!("<li><a href=\"{}\">{}</a></li>", blog.domain, blog.name));
+    }
+    html_output.push_str("</ul>");
+
+    // Add errors if any
+    if !report.errors.is_empty() {
+        html_output.push_str("<h3>Errors:</h3><ul>");
+        for (blog_name, error) in report.errors {
+            html_output.push_str(&format!("<li><strong>{}</strong>: {}</li>", blog_name, error));
+        }
+        html_output.push_str("</ul>");
+    }
+
+    // Add warnings if any
+    if !report.warnings.is_empty() {
+        html_output.push_str("<h3>Warnings:</h3><ul>");
+        for (blog_name, warning) in report.warnings {
+            html_output.push_str(&format!("<li><strong>{}</strong>: {}</li>", blog_name, warning));
+        }
+        html_output.push_str("</ul>");
+    }
+
+    // Add entries that could not be read, counted per source
+    if !report.skipped.is_empty() {
+        html_output.push_str("<h3>Skipped Entries:</h3><ul>");
+        for items in report.skipped.chunk_by(|(a, _), (b, _)| a == b) {
+            html_output.push_str(&format!("<li><strong>{}</strong>: {} skipped<ul>", items[0].0, items.len()));
+            for (_, skipped) in items {
+                html_output.push_str(&format!("<li>{}: {}</li>", skipped.item, skipped.reason));
+            }
+            html_output.push_str("</ul></li>");
+        }
+        html_output.push_str("</ul>");
+    }
+
+    // Add every HTTP attempt, retries included
+    if !report.attempts.is_empty() {
+        html_output.push_str("<h3>Fetch Attempts:</h3><ul>");
+        for (blog_name, attempt) in report.attempts {
+            let retry = attempt.retry_in
+                .map(|delay| format!(", retrying in {:.1}s", delay.as_secs_f64()))
+                .unwrap_or_default();
+            html_output.push_str(&format!("<li><strong>{}</strong>: attempt {} on {}: {}{}</li>", blog_name, attempt.attempt, attempt.url, attempt.outcome, retry));
+        }
+        html_output.push_str("</ul>");
+    }
+
+    html_output.push_str("</body></html>");
+    html_output
+}
+
+/// List item of an ePrint or arXiv paper, with its revision date and the rules that selected it.
+fn paper_item(article: BlogArticle, timezone: Tz) -> String {
+    let authors_or_blog_name = article.authors.clone().unwrap_or_else(|| "Unknown Author".to_string());
+    let capitalized_title = capitalize_title(&article.title);
+    let revised = article.updated
+        .map(|updated| format!(" | revised {}", report_date(updated, timezone)))
+        .unwrap_or_default();
+    let matched_rule = match (article.score, article.matched_rule) {
+        (Some(score), Some(rule)) => format!(" | <em>score {}: {}</em>", score, rule),
+        (None, Some(rule)) => format!(" | <em>{}</em>", rule),
+        _ => String::new(),
+    };
+    format!("<li><a href=\"{}\">{}</a> | {}{}{}</li>", article.url, capitalized_title, authors_or_blog_name, revised, matched_rule)
+}
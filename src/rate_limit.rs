@@ -0,0 +1,115 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use reqwest::Url;
+use serde::Deserialize;
+use tokio::time::{Duration, Instant};
+
+/// Throttling settings, read from the `rate_limit` section of the settings file.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct RateLimitConfig {
+    /// Maximum number of sources fetched at the same time.
+    pub max_concurrent_sources: usize,
+    /// Sustained request rate allowed per host. `0` disables per-host throttling.
+    pub requests_per_second: f64,
+    /// Number of requests a host may receive back to back before being throttled.
+    pub burst: u32,
+    /// Per-host overrides, e.g. `{"medium.com": 0.5}`.
+    pub hosts: HashMap<String, HostLimit>,
+}
+
+/// Throttling of one host of [`RateLimitConfig::hosts`]: either its rate, or
+/// an object also overriding the burst, e.g. `{"requests_per_second": 0.33, "burst": 1}`.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum HostLimit {
+    Rate(f64),
+    Bucket {
+        requests_per_second: f64,
+        burst: Option<u32>,
+    },
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        RateLimitConfig {
+            max_concurrent_sources: 8,
+            requests_per_second: 2.0,
+            burst: 4,
+            hosts: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+/// Token bucket per host, shared by all sources of a run.
+///
+/// Requests to the same host are spaced according to the configured rate while
+/// requests to different hosts never wait on each other.
+#[derive(Debug, Clone)]
+pub struct HostRateLimiter {
+    config: Arc<RateLimitConfig>,
+    buckets: Arc<Mutex<HashMap<String, Bucket>>>,
+}
+
+impl HostRateLimiter {
+    pub fn new(config: &RateLimitConfig) -> Self {
+        HostRateLimiter {
+            config: Arc::new(config.clone()),
+            buckets: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Waits until a request to the host of `url` is allowed.
+    pub async fn acquire(&self, url: &str) {
+        let host = match Url::parse(url).ok().and_then(|url| url.host_str().map(str::to_string)) {
+            Some(host) => host,
+            None => return,
+        };
+        let wait = self.reserve(&host);
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Takes a token for `host` and returns how long the caller must wait for it.
+    ///
+    /// Tokens may go negative: each caller reserves its slot, so waiters are
+    /// served in the order they arrived.
+    fn reserve(&self, host: &str) -> Duration {
+        let (rate, burst) = match self.config.hosts.get(host) {
+            Some(HostLimit::Rate(rate)) => (*rate, self.config.burst),
+            Some(HostLimit::Bucket { requests_per_second, burst }) => (*requests_per_second, burst.unwrap_or(self.config.burst)),
+            None => (self.config.requests_per_second, self.config.burst),
+        };
+        if rate <= 0.0 {
+            return Duration::ZERO;
+        }
+        let burst = burst.max(1) as f64;
+
+        let mut buckets = self.buckets.lock().expect("Rate limiter lock poisoned");
+        let now = Instant::now();
+        let bucket = buckets.entry(host.to_string()).or_insert(Bucket { tokens: burst, last_refill: now });
+        let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * rate).min(burst);
+        bucket.last_refill = now;
+        bucket.tokens -= 1.0;
+
+        if bucket.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-bucket.tokens / rate)
+        }
+    }
+}
+
+impl Default for HostRateLimiter {
+    fn default() -> Self {
+        HostRateLimiter::new(&RateLimitConfig::default())
+    }
+}
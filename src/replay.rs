@@ -0,0 +1,98 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use crate::body::{file_stream, PendingWrite, StreamingResponse};
+use crate::cache::cache_key;
+use crate::errors::AppError;
+use crate::http::HttpResponse;
+
+/// Whether a run saves its HTTP responses as fixtures or serves them from fixtures.
+#[derive(Debug, Clone)]
+pub enum FixtureMode {
+    /// Fetch from the network and save every response.
+    Record(FixtureStore),
+    /// Serve every response from the fixtures, without any network access.
+    Replay(FixtureStore),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Fixture {
+    url: String,
+    status: u16,
+    headers: Vec<(String, String)>,
+}
+
+impl Fixture {
+    fn new(url: &str, status: StatusCode, headers: &HeaderMap) -> Self {
+        Fixture {
+            url: url.to_string(),
+            status: status.as_u16(),
+            headers: headers.iter()
+                .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+                .collect(),
+        }
+    }
+}
+
+/// Directory of raw HTTP responses keyed by URL.
+///
+/// Each URL is stored as `<key>.json` (URL, status and headers) next to
+/// `<key>.body`, so bodies can be inspected or edited by hand.
+#[derive(Debug, Clone)]
+pub struct FixtureStore {
+    dir: PathBuf,
+}
+
+impl FixtureStore {
+    pub fn new(dir: impl AsRef<Path>) -> Self {
+        FixtureStore { dir: dir.as_ref().to_path_buf() }
+    }
+
+    /// Saves `response` for `url` as if it had been recorded, e.g. to build fixtures by hand.
+    pub fn save(&self, url: &str, response: &HttpResponse) -> Result<(), AppError> {
+        let mut write = self.begin_save(url, response.status, &response.headers)?;
+        write.write(&response.body)?;
+        Ok(write.commit()?)
+    }
+
+    /// Returns the recorded response for `url`, streaming the body from disk.
+    pub(crate) async fn open(&self, url: &str) -> Result<StreamingResponse, AppError> {
+        let (status, headers) = self.load_meta(url)?;
+        let body = file_stream(&self.paths(url).1).await?;
+        Ok(StreamingResponse { status, headers, body })
+    }
+
+    /// Starts recording a response for `url`, saved once its body was read to the end.
+    pub(crate) fn begin_save(&self, url: &str, status: StatusCode, headers: &HeaderMap) -> Result<PendingWrite, AppError> {
+        let meta = serde_json::to_vec_pretty(&Fixture::new(url, status, headers))?;
+        let (meta_path, body_path) = self.paths(url);
+        Ok(PendingWrite::create(body_path, meta_path, meta, true)?)
+    }
+
+    fn load_meta(&self, url: &str) -> Result<(StatusCode, HeaderMap), AppError> {
+        let meta_path = self.paths(url).0;
+        let meta = fs::read(&meta_path)
+            .map_err(|e| AppError::HttpError(format!("No recorded response for {}: {}", url, e)))?;
+        let fixture: Fixture = serde_json::from_slice(&meta)?;
+        if fixture.url != url {
+            return Err(AppError::HttpError(format!("Fixture {} was recorded for {}", meta_path.display(), fixture.url)));
+        }
+
+        let status = StatusCode::from_u16(fixture.status)
+            .map_err(|e| AppError::HttpError(format!("Invalid status in fixture for {}: {}", url, e)))?;
+        let mut headers = HeaderMap::new();
+        for (name, value) in fixture.headers {
+            if let (Ok(name), Ok(value)) = (HeaderName::from_bytes(name.as_bytes()), HeaderValue::from_str(&value)) {
+                headers.append(name, value);
+            }
+        }
+        Ok((status, headers))
+    }
+
+    fn paths(&self, url: &str) -> (PathBuf, PathBuf) {
+        let key = cache_key(url);
+        (self.dir.join(format!("{}.json", key)), self.dir.join(format!("{}.body", key)))
+    }
+}
@@ -0,0 +1,74 @@
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use rand::Rng;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::StatusCode;
+use serde::Deserialize;
+
+/// How failed HTTP requests are retried, read from the `retry` section of the settings file.
+///
+/// Sources can override `max_attempts` with the `max_attempts` field of their `BlogInfo`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct RetryPolicy {
+    /// Total number of attempts, the first one included. `1` disables retries.
+    pub max_attempts: u32,
+    pub initial_backoff_ms: u64,
+    /// Upper bound for a single wait, `Retry-After` included.
+    pub max_backoff_ms: u64,
+    pub multiplier: f64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 3,
+            initial_backoff_ms: 500,
+            max_backoff_ms: 30_000,
+            multiplier: 2.0,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Wait before the attempt following `attempt` (1-based).
+    ///
+    /// A `Retry-After` value sent by the server takes precedence. Otherwise the
+    /// exponential backoff is jittered to a random value between half and all of it.
+    pub fn delay(&self, attempt: u32, retry_after: Option<Duration>) -> Duration {
+        let max_backoff = Duration::from_millis(self.max_backoff_ms);
+        if let Some(retry_after) = retry_after {
+            return retry_after.min(max_backoff);
+        }
+
+        let exponent = attempt.saturating_sub(1) as i32;
+        let backoff_ms = (self.initial_backoff_ms as f64 * self.multiplier.powi(exponent))
+            .min(self.max_backoff_ms as f64);
+        let jittered_ms = if backoff_ms >= 2.0 {
+            rand::thread_rng().gen_range(backoff_ms / 2.0..=backoff_ms)
+        } else {
+            backoff_ms
+        };
+        Duration::from_millis(jittered_ms as u64)
+    }
+}
+
+/// Statuses worth another try: timeouts, rate limiting and transient server errors.
+pub fn is_retryable_status(status: StatusCode) -> bool {
+    matches!(status.as_u16(), 408 | 429 | 500 | 502 | 503 | 504)
+}
+
+/// Transport failures worth another try, e.g. timeouts and connection resets.
+pub fn is_retryable_error(error: &reqwest::Error) -> bool {
+    error.is_timeout() || error.is_connect() || error.is_request() || error.is_body()
+}
+
+/// Parses a `Retry-After` header given either in seconds or as an HTTP date.
+pub fn parse_retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    Some((date.with_timezone(&Utc) - Utc::now()).to_std().unwrap_or(Duration::ZERO))
+}
@@ -0,0 +1,386 @@
+//! Parser for every RSS and Atom version, whatever a source declares itself as.
+
+use std::fmt;
+use quick_xml::escape::unescape;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::name::{Namespace, ResolveResult};
+use quick_xml::NsReader;
+use reqwest::Url;
+use crate::errors::AppError;
+
+const ATOM_10_NS: &[u8] = b"http://www.w3.org/2005/Atom";
+const ATOM_03_NS: &[u8] = b"http://purl.org/atom/ns#";
+const RSS_10_NS: &[u8] = b"http://purl.org/rss/1.0/";
+const RSS_090_NS: &[u8] = b"http://my.netscape.com/rdf/simple/0.9/";
+const DC_NS: &[u8] = b"http://purl.org/dc/elements/1.1/";
+
+/// Syndication format of a parsed document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeedFormat {
+    /// RSS 0.90, the first RDF-based RSS.
+    Rss090,
+    /// RSS 0.91 to 0.94.
+    Rss09x,
+    /// RSS 1.0 (RDF).
+    Rss10,
+    Rss20,
+    Atom03,
+    Atom10,
+}
+
+impl FeedFormat {
+    pub fn is_atom(self) -> bool {
+        matches!(self, FeedFormat::Atom03 | FeedFormat::Atom10)
+    }
+}
+
+impl fmt::Display for FeedFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            FeedFormat::Rss090 => "RSS 0.90",
+            FeedFormat::Rss09x => "RSS 0.9x",
+            FeedFormat::Rss10 => "RSS 1.0",
+            FeedFormat::Rss20 => "RSS 2.0",
+            FeedFormat::Atom03 => "Atom 0.3",
+            FeedFormat::Atom10 => "Atom 1.0",
+        })
+    }
+}
+
+/// An RSS item or Atom entry, with the text of its elements as written.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FeedEntry {
+    pub title: Option<String>,
+    /// RSS `<link>`, Atom alternate link, or failing those a `<guid>` declared a
+    /// permalink, or that is an absolute URL when it does not say.
+    /// Relative links are resolved against `xml:base` where the feed sets one.
+    pub link: Option<String>,
+    /// RSS `pubDate` or `dc:date`, Atom `published` (1.0) or `issued` (0.3).
+    pub published: Option<String>,
+    /// Atom `updated` (1.0) or `modified` (0.3).
+    pub updated: Option<String>,
+    /// Names of the Atom `<author>`s or `dc:creator`s.
+    pub authors: Vec<String>,
+}
+
+/// A parsed feed document.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedFeed {
+    pub format: FeedFormat,
+    pub entries: Vec<FeedEntry>,
+    /// Atom authors of the whole feed, for entries that name none.
+    pub authors: Vec<String>,
+}
+
+/// Namespaces told apart by the parser.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Ns {
+    /// RSS 0.9x and 2.0 elements, which have no namespace.
+    None,
+    Rss,
+    Atom,
+    Dc,
+    Other,
+}
+
+impl Ns {
+    fn of(resolved: &ResolveResult) -> Self {
+        match resolved {
+            ResolveResult::Unbound => Ns::None,
+            ResolveResult::Bound(Namespace(RSS_10_NS | RSS_090_NS)) => Ns::Rss,
+            ResolveResult::Bound(Namespace(ATOM_10_NS | ATOM_03_NS)) => Ns::Atom,
+            ResolveResult::Bound(Namespace(DC_NS)) => Ns::Dc,
+            _ => Ns::Other,
+        }
+    }
+
+    fn is_rss(self) -> bool {
+        matches!(self, Ns::None | Ns::Rss)
+    }
+}
+
+/// Element of an entry whose text is being read.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Field {
+    Title,
+    Link,
+    Guid,
+    PubDate,
+    DcDate,
+    Published,
+    Created,
+    Updated,
+    Creator,
+    AuthorName,
+}
+
+impl Field {
+    fn of(ns: Ns, local_name: &[u8]) -> Option<Self> {
+        match (ns, local_name) {
+            (ns, b"title") if ns.is_rss() || ns == Ns::Atom => Some(Field::Title),
+            (ns, b"link") if ns.is_rss() => Some(Field::Link),
+            (Ns::None, b"guid") => Some(Field::Guid),
+            (Ns::None, b"pubDate") => Some(Field::PubDate),
+            (Ns::Dc, b"date") => Some(Field::DcDate),
+            (Ns::Atom, b"published" | b"issued") => Some(Field::Published),
+            (Ns::Atom, b"created") => Some(Field::Created),
+            (Ns::Atom, b"updated" | b"modified") => Some(Field::Updated),
+            (Ns::Dc, b"creator") => Some(Field::Creator),
+            _ => None,
+        }
+    }
+}
+
+/// An Atom `<link>`, with its `href` resolved.
+struct Link {
+    rel: String,
+    media_type: String,
+    href: String,
+}
+
+impl Link {
+    fn is_alternate(&self) -> bool {
+        self.rel.is_empty() || self.rel == "alternate"
+    }
+
+    fn is_html(&self) -> bool {
+        self.media_type.is_empty() || self.media_type.contains("html")
+    }
+}
+
+/// Entry being read, with what is only known once it ends.
+#[derive(Default)]
+struct PartialEntry {
+    entry: FeedEntry,
+    links: Vec<Link>,
+    /// RSS `<guid>` that is a permalink.
+    permalink: Option<String>,
+    /// Whether the `<guid>` being read says `isPermaLink="true"`.
+    declared_permalink: bool,
+    dc_date: Option<String>,
+    created: Option<String>,
+}
+
+impl PartialEntry {
+    fn finish(self) -> FeedEntry {
+        let mut entry = self.entry;
+        // The HTML alternate is the article, links about the entry itself never are
+        let alternate = self.links.iter().find(|link| link.is_alternate() && link.is_html())
+            .or_else(|| self.links.iter().find(|link| link.is_alternate()))
+            .or_else(|| self.links.iter().find(|link| !matches!(link.rel.as_str(), "self" | "edit" | "edit-media" | "replies" | "enclosure")))
+            .map(|link| link.href.clone());
+        entry.link = entry.link.or(alternate).or(self.permalink);
+        entry.published = entry.published.or(self.dc_date).or(self.created);
+        entry
+    }
+}
+
+fn attribute(reader: &NsReader<&[u8]>, element: &BytesStart, name: &str) -> Option<String> {
+    element.try_get_attribute(name).ok().flatten()
+        .and_then(|attr| attr.decode_and_unescape_value(reader.decoder()).ok())
+        .map(|value| value.trim().to_string())
+}
+
+fn is_absolute_url(value: &str) -> bool {
+    Url::parse(value).is_ok_and(|url| matches!(url.scheme(), "http" | "https"))
+}
+
+/// Unescapes the references of `raw` that XML knows, leaving the others as written.
+fn unescape_known(raw: &str) -> String {
+    let mut text = String::with_capacity(raw.len());
+    let mut rest = raw;
+    while let Some(start) = rest.find('&') {
+        text.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let reference = rest.find(';')
+            .map(|end| &rest[..=end])
+            .filter(|reference| !reference[1..].contains('&'));
+        match reference.and_then(|reference| Some((reference, unescape(reference).ok()?))) {
+            Some((reference, unescaped)) => {
+                text.push_str(&unescaped);
+                rest = &rest[reference.len()..];
+            }
+            None => {
+                text.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    text.push_str(rest);
+    text
+}
+
+/// Resolves `href` against `base`, leaving it as is when there is no absolute base.
+fn resolve(base: Option<&str>, href: &str) -> String {
+    base.and_then(|base| Url::parse(base).ok())
+        .and_then(|base| base.join(href).ok())
+        .map_or_else(|| href.to_string(), String::from)
+}
+
+/// Base URI of `element`, from its own `xml:base` or else the one it is nested in.
+fn element_base(reader: &NsReader<&[u8]>, element: &BytesStart, bases: &[(usize, String)]) -> Option<String> {
+    let parent = bases.last().map(|(_, base)| base.as_str());
+    match attribute(reader, element, "xml:base") {
+        Some(base) => Some(resolve(parent, &base)),
+        None => parent.map(str::to_string),
+    }
+}
+
+/// Format of a document whose root element is `element`.
+fn root_format(reader: &NsReader<&[u8]>, ns: Ns, element: &BytesStart) -> Result<FeedFormat, AppError> {
+    match element.local_name().as_ref() {
+        b"rss" => match attribute(reader, element, "version").as_deref() {
+            Some(version) if version.starts_with("0.9") => Ok(FeedFormat::Rss09x),
+            _ => Ok(FeedFormat::Rss20),
+        },
+        b"RDF" => match attribute(reader, element, "xmlns") {
+            Some(ns) if ns.as_bytes() == RSS_090_NS => Ok(FeedFormat::Rss090),
+            _ => Ok(FeedFormat::Rss10),
+        },
+        b"feed" => {
+            let ns = attribute(reader, element, "xmlns");
+            let version = attribute(reader, element, "version");
+            if ns.as_deref().map(str::as_bytes) == Some(ATOM_03_NS) || version.as_deref() == Some("0.3") {
+                Ok(FeedFormat::Atom03)
+            } else {
+                Ok(FeedFormat::Atom10)
+            }
+        }
+        _ => Err(AppError::ParseError(format!(
+            "Not an RSS or Atom feed: root element <{}>{}",
+            String::from_utf8_lossy(element.name().as_ref()),
+            if ns == Ns::Other { " in an unknown namespace" } else { "" },
+        ))),
+    }
+}
+
+/// Parses an RSS 0.90, 0.9x, 1.0 (RDF) or 2.0 document, or an Atom 0.3 or 1.0
+/// one, telling them apart by their root element and namespaces.
+pub fn parse_feed(content: &[u8]) -> Result<ParsedFeed, AppError> {
+    let mut reader = NsReader::from_reader(content);
+    let mut buf = Vec::new();
+    let mut format = None;
+    let mut entries = Vec::new();
+    let mut entry: Option<PartialEntry> = None;
+    // Depth of the current element, and of the entry and field being read
+    let mut depth = 0;
+    let mut entry_depth = 0;
+    let mut field: Option<Field> = None;
+    let mut text = String::new();
+    // Depth of the Atom `<author>` being read, and the authors of the feed itself
+    let mut author_depth: Option<usize> = None;
+    let mut feed_authors = Vec::new();
+    // `xml:base` in effect, with the depth of the element that set it
+    let mut bases: Vec<(usize, String)> = Vec::new();
+
+    loop {
+        buf.clear();
+        let (resolved, event) = reader.read_resolved_event_into(&mut buf)?;
+        let ns = Ns::of(&resolved);
+        match event {
+            Event::Start(ref element) | Event::Empty(ref element) => {
+                let is_empty = matches!(event, Event::Empty(_));
+                let local_name = element.local_name();
+                let local_name = local_name.as_ref();
+                let base = element_base(&reader, element, &bases);
+                if let Some(own_base) = base.as_ref().filter(|_| !is_empty && attribute(&reader, element, "xml:base").is_some()) {
+                    bases.push((depth, own_base.clone()));
+                }
+                let in_author = author_depth.is_some_and(|author| depth == author + 1);
+                if format.is_none() {
+                    format = Some(root_format(&reader, ns, element)?);
+                } else if ns == Ns::Atom && local_name == b"name" && in_author {
+                    field = (!is_empty).then_some(Field::AuthorName);
+                    text.clear();
+                } else if ns == Ns::Atom && local_name == b"author" && !is_empty
+                    && (if entry.is_some() { depth == entry_depth + 1 } else { depth == 1 }) {
+                    author_depth = Some(depth);
+                } else if let Some(current) = entry.as_mut() {
+                    if depth == entry_depth + 1 {
+                        if ns == Ns::Atom && local_name == b"link" {
+                            if let Some(href) = attribute(&reader, element, "href") {
+                                current.links.push(Link {
+                                    rel: attribute(&reader, element, "rel").unwrap_or_default(),
+                                    media_type: attribute(&reader, element, "type").unwrap_or_default(),
+                                    href: resolve(base.as_deref(), &href),
+                                });
+                            }
+                        } else if let Some(new_field) = Field::of(ns, local_name) {
+                            let is_permalink = attribute(&reader, element, "isPermaLink");
+                            if new_field == Field::Guid {
+                                current.declared_permalink = is_permalink.as_deref() == Some("true");
+                            }
+                            field = (new_field != Field::Guid || is_permalink.as_deref() != Some("false")).then_some(new_field);
+                            text.clear();
+                        }
+                    }
+                } else if (ns.is_rss() && local_name == b"item") || (ns == Ns::Atom && local_name == b"entry") {
+                    if is_empty {
+                        entries.push(PartialEntry::default().finish());
+                        continue;
+                    }
+                    entry = Some(PartialEntry::default());
+                    entry_depth = depth;
+                }
+                if !is_empty {
+                    depth += 1;
+                } else if depth == entry_depth + 1 {
+                    field = None;
+                }
+            }
+            Event::Text(ref e) if field.is_some() => {
+                match e.unescape() {
+                    Ok(unescaped) => text.push_str(&unescaped),
+                    // HTML entities such as `&nbsp;` are not XML ones
+                    Err(_) => text.push_str(&unescape_known(&String::from_utf8_lossy(e))),
+                }
+            }
+            Event::CData(ref e) if field.is_some() => text.push_str(&String::from_utf8_lossy(e)),
+            Event::End(_) => {
+                depth -= 1;
+                if bases.last().is_some_and(|(base_depth, _)| *base_depth == depth) {
+                    bases.pop();
+                }
+                if author_depth == Some(depth) {
+                    author_depth = None;
+                } else if field == Some(Field::AuthorName) && author_depth.is_some_and(|author| depth == author + 1) {
+                    field = None;
+                    let name = text.trim().to_string();
+                    if !name.is_empty() {
+                        match entry.as_mut() {
+                            Some(current) => current.entry.authors.push(name),
+                            None => feed_authors.push(name),
+                        }
+                    }
+                } else if entry.is_some() && depth == entry_depth {
+                    entries.extend(entry.take().map(PartialEntry::finish));
+                } else if let Some(current) = entry.as_mut().filter(|_| depth == entry_depth + 1) {
+                    if let Some(field) = field.take() {
+                        let value = text.trim().to_string();
+                        match field {
+                            Field::Title => current.entry.title = Some(value),
+                            Field::Link => {
+                                let base = bases.last().map(|(_, base)| base.as_str());
+                                current.entry.link = Some(value).filter(|link| !link.is_empty()).map(|link| resolve(base, &link));
+                            }
+                            // Without `isPermaLink`, an identifier such as `abc-123` is no link
+                            Field::Guid if current.declared_permalink || is_absolute_url(&value) => current.permalink = Some(value),
+                            Field::Guid => {}
+                            Field::PubDate | Field::Published => current.entry.published = Some(value),
+                            Field::DcDate => current.dc_date = Some(value),
+                            Field::Created => current.created = Some(value),
+                            Field::Updated => current.entry.updated = Some(value),
+                            Field::Creator => current.entry.authors.extend(Some(value).filter(|name| !name.is_empty())),
+                            Field::AuthorName => {}
+                        }
+                    }
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    let format = format.ok_or_else(|| AppError::ParseError("Empty feed document".to_string()))?;
+    Ok(ParsedFeed { format, entries, authors: feed_authors })
+}
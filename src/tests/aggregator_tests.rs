@@ -0,0 +1,393 @@
+use crate::aggregator::Aggregator;
+use crate::config::read_blogs_from_file;
+use chrono::{NaiveDate, TimeZone, Utc};
+use crate::models::BlogInfo;
+use crate::dates::DateWindow;
+use std::fs::File;
+use std::io::Write;
+use tempfile::NamedTempFile;
+use mockito::mock;
+
+#[tokio::test]
+async fn test_aggregator_fetch() {
+    // Set up mock servers for different feed types
+    let substack_mock = mock("GET", "/api/v1/posts/?limit=50")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"[{"title":"Test Substack Article","slug":"test-article","post_date":"2024-10-01T00:00:00.000Z"}]"#)
+        .create();
+
+    let rss_mock = mock("GET", "/")
+        .with_status(200)
+        .with_header("content-type", "application/rss+xml")
+        .with_body(r#"
+            <?xml version="1.0" encoding="UTF-8"?>
+            <rss version="2.0">
+                <channel>
+                    <item>
+                        <title>Test RSS Article</title>
+                        <link>https://test.com/rss-article</link>
+                        <pubDate>Tue, 01 Oct 2024 12:00:00 GMT</pubDate>
+                    </item>
+                </channel>
+            </rss>
+        "#)
+        .create();
+
+    let atom_mock = mock("GET", "/")
+        .with_status(200)
+        .with_header("content-type", "application/atom+xml")
+        .with_body(r#"
+            <?xml version="1.0" encoding="utf-8"?>
+            <feed xmlns="http://www.w3.org/2005/Atom">
+                <entry>
+                    <title>Test Atom Article</title>
+                    <link href="https://test.com/atom-article"/>
+                    <updated>2024-10-01T12:00:00Z</updated>
+                </entry>
+            </feed>
+        "#)
+        .create();
+
+    let custom_html_mock = mock("GET", "/custom")
+        .with_status(200)
+        .with_header("content-type", "text/html")
+        .with_body(r#"
+            <html>
+                <body>
+                    <div class="post-feed">
+                        <article>
+                            <h2 class="post-card-title"><a href="https://test.com/custom-article">Test Custom HTML Article</a></h2>
+                            <time class="post-card-meta-date">October 1, 2024</time>
+                        </article>
+                    </div>
+                </body>
+            </html>
+        "#)
+        .create();
+
+    // Create a temporary file with test blog data
+    let temp_file = NamedTempFile::new().expect("Failed to create temporary file");
+    let path = temp_file.path().to_str().expect("Failed to get path as string");
+
+    let file = File::create(path).expect("Failed to create file");
+    let json_content = serde_json::json!([
+        {
+            "name": "TestSubstack",
+            "domain": format!("{}/api/v1/posts/?limit=50", mockito::server_url()),
+            "feed_type": "Substack"
+        },
+        {
+            "name": "TestRSS",
+            "domain": mockito::server_url(),
+            "feed_type": "RSS"
+        },
+        {
+            "name": "TestAtom",
+            "domain": mockito::server_url(),
+            "feed_type": "Atom"
+        },
+        {
+            "name": "TestCustomHTML",
+            "domain": format!("{}/custom", mockito::server_url()),
+            "feed_type": "CustomHTML",
+            "custom_selectors": {
+                "article_selector": "div.post-feed article",
+                "title_selector": "h2.post-card-title a",
+                "article_item_selector": "div.post-feed article",
+                "url_selector": "h2.post-card-title a[href]",
+                "date_selector": "time.post-card-meta-date",
+                "date_format": "%B %d, %Y"
+            }
+        }
+    ]);
+    serde_json::to_writer_pretty(file, &json_content).expect("Failed to write JSON to file");
+
+    let blogs = read_blogs_from_file(path).expect("Failed to read blogs");
+    let window = DateWindow::since(NaiveDate::from_ymd_opt(2024, 9, 1).unwrap());
+    let report = Aggregator::new(blogs).fetch(&window).await;
+    assert!(report.errors.is_empty(), "unexpected errors: {:?}", report.errors);
+
+    // Assert that the mocks were called
+    substack_mock.assert();
+    rss_mock.assert();
+    atom_mock.assert();
+    custom_html_mock.assert();
+}
+
+#[tokio::test]
+async fn test_aggregator_fetch_splits_and_sorts_articles() {
+    // Set up mock servers for different feed types
+    let substack_mock = mock("GET", "/api/v1/posts/?limit=50")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"[{"title":"Test Substack Article","slug":"test-article","post_date":"2024-10-01T00:00:00.000Z"}]"#)
+        .create();
+
+    let rss_mock = mock("GET", "/rss")
+        .with_status(200)
+        .with_header("content-type", "application/rss+xml")
+        .with_body(r#"
+            <?xml version="1.0" encoding="UTF-8"?>
+            <rss version="2.0">
+                <channel>
+                    <item>
+                        <title>Test RSS Article</title>
+                        <link>https://test.com/rss-article</link>
+                        <pubDate>Tue, 01 Oct 2024 12:00:00 GMT</pubDate>
+                    </item>
+                </channel>
+            </rss>
+        "#)
+        .create();
+
+    let atom_mock = mock("GET", "/atom")
+        .with_status(200)
+        .with_header("content-type", "application/atom+xml")
+        .with_body(r#"
+            <?xml version="1.0" encoding="utf-8"?>
+            <feed xmlns="http://www.w3.org/2005/Atom">
+                <entry>
+                    <title>Test Atom Article</title>
+                    <link href="https://test.com/atom-article"/>
+                    <updated>2024-10-01T12:00:00Z</updated>
+                </entry>
+            </feed>
+        "#)
+        .create();
+
+    let custom_html_mock = mock("GET", "/custom")
+        .with_status(200)
+        .with_header("content-type", "text/html")
+        .with_body(r#"
+            <html>
+                <body>
+                    <div class="post-feed">
+                        <article>
+                            <h2 class="post-card-title"><a href="https://test.com/custom-article">Test Custom HTML Article</a></h2>
+                            <time class="post-card-meta-date">October 1, 2024</time>
+                        </article>
+                    </div>
+                </body>
+            </html>
+        "#)
+        .create();
+
+    // Create a temporary file with test blog data
+    let temp_file = NamedTempFile::new().expect("Failed to create temporary file");
+    let path = temp_file.path().to_str().expect("Failed to get path as string");
+
+    let file = File::create(path).expect("Failed to create file");
+    let json_content = serde_json::json!([
+        {
+            "name": "TestSubstack",
+            "domain": format!("{}/api/v1/posts/?limit=50", mockito::server_url()),
+            "feed_type": "Substack"
+        },
+        {
+            "name": "TestRSS",
+            "domain": format!("{}/rss", mockito::server_url()),
+            "feed_type": "RSS"
+        },
+        {
+            "name": "TestAtom",
+            "domain": format!("{}/atom", mockito::server_url()),
+            "feed_type": "Atom"
+        },
+        {
+            "name": "TestCustomHTML",
+            "domain": format!("{}/custom", mockito::server_url()),
+            "feed_type": "CustomHTML",
+            "custom_selectors": {
+                "article_selector": "div.post-feed",
+                "article_item_selector": "article",
+                "title_selector": "h2.post-card-title a",
+                "url_selector": "h2.post-card-title a[href]",
+                "date_selector": "time.post-card-meta-date",
+                "date_format": "%B %d, %Y"
+            }
+        }
+    ]);
+    serde_json::to_writer_pretty(file, &json_content).expect("Failed to write JSON to file");
+
+    let blogs = read_blogs_from_file(path).expect("Failed to read blogs");
+    let window = DateWindow::since(NaiveDate::from_ymd_opt(2024, 9, 1).unwrap());
+    let report = Aggregator::new(blogs).fetch(&window).await;
+    assert!(report.errors.is_empty(), "unexpected errors: {:?}", report.errors);
+    assert!(report.eprint_articles.is_empty());
+    assert_eq!(report.other_articles.len(), 4);
+    // Sorted by publication time, keeping the source order between equal times
+    let blog_names: Vec<&str> = report.other_articles.iter().map(|a| a.blog_name.as_str()).collect();
+    assert_eq!(blog_names, vec!["TestSubstack", "TestCustomHTML", "TestRSS", "TestAtom"]);
+
+    // Assert that the mocks were called
+    substack_mock.assert();
+    rss_mock.assert();
+    atom_mock.assert();
+    custom_html_mock.assert();
+}
+
+#[tokio::test]
+async fn test_aggregator_reports_source_errors() {
+    let _m = mock("GET", "/broken")
+        .with_status(200)
+        .with_header("content-type", "application/rss+xml")
+        .with_body("not a feed")
+        .create();
+
+    let temp_file = NamedTempFile::new().expect("Failed to create temporary file");
+    let path = temp_file.path().to_str().expect("Failed to get path as string");
+
+    let file = File::create(path).expect("Failed to create file");
+    let json_content = serde_json::json!([
+        {
+            "name": "BrokenRSS",
+            "domain": format!("{}/broken", mockito::server_url()),
+            "feed_type": "RSS"
+        },
+        {
+            "name": "NoSelectors",
+            "domain": "https://test.com",
+            "feed_type": "CustomHTML"
+        }
+    ]);
+    serde_json::to_writer_pretty(file, &json_content).expect("Failed to write JSON to file");
+
+    let blogs = read_blogs_from_file(path).expect("Failed to read blogs");
+    let window = DateWindow::since(NaiveDate::from_ymd_opt(2024, 9, 1).unwrap());
+    let report = Aggregator::new(blogs).fetch(&window).await;
+
+    assert!(report.other_articles.is_empty());
+    assert_eq!(report.errors.len(), 2);
+    assert!(report.errors.iter().any(|(name, _)| name == "BrokenRSS"));
+    assert!(report.errors.iter().any(|(name, e)| name == "NoSelectors" && e.contains("Missing custom selectors")));
+}
+
+#[tokio::test]
+async fn test_aggregator_merges_eprint_profiles() {
+    let _m = mock("GET", "/profiles?from=2024-09-01")
+        .with_status(200)
+        .with_body(r#"<OAI-PMH><ListRecords>
+            <record><metadata><oai_dc:dc>
+              <dc:identifier>https://eprint.iacr.org/2024/1</dc:identifier>
+              <dc:title>Homomorphic zero-knowledge proofs</dc:title>
+              <dc:date>2024-09-18T07:46:25Z</dc:date>
+            </oai_dc:dc></metadata></record>
+            <record><metadata><oai_dc:dc>
+              <dc:identifier>https://eprint.iacr.org/2024/2</dc:identifier>
+              <dc:title>Faster bootstrapping for homomorphic encryption</dc:title>
+              <dc:date>2024-09-19T07:46:25Z</dc:date>
+            </oai_dc:dc></metadata></record>
+        </ListRecords></OAI-PMH>"#)
+        .create();
+
+    let mut zk_config = NamedTempFile::new().expect("Failed to create temporary file");
+    zk_config.write_all(br#"{"keywords": ["zero-knowledge"]}"#).expect("Failed to write config");
+    let mut fhe_config = NamedTempFile::new().expect("Failed to create temporary file");
+    fhe_config.write_all(br#"{"keywords": ["homomorphic"]}"#).expect("Failed to write config");
+
+    let domain = format!("{}/profiles", mockito::server_url());
+    let blogs: Vec<BlogInfo> = serde_json::from_value(serde_json::json!([
+        {
+            "name": "ePrint ZK",
+            "domain": domain,
+            "feed_type": "Eprint",
+            "eprint_config": zk_config.path().to_str().unwrap()
+        },
+        {
+            "name": "ePrint FHE",
+            "domain": domain,
+            "feed_type": "Eprint",
+            "eprint_config": fhe_config.path().to_str().unwrap()
+        }
+    ])).expect("Failed to build blogs");
+
+    let window = DateWindow::since(NaiveDate::from_ymd_opt(2024, 9, 1).unwrap());
+    let report = Aggregator::new(blogs).fetch(&window).await;
+
+    assert!(report.errors.is_empty());
+    let urls: Vec<&str> = report.eprint_articles.iter().map(|a| a.url.as_str()).collect();
+    assert_eq!(urls, vec!["https://eprint.iacr.org/2024/1", "https://eprint.iacr.org/2024/2"]);
+}
+
+#[tokio::test]
+async fn test_aggregator_lists_revised_eprint_papers_separately() {
+    let _m = mock("GET", "/revisions?from=2024-09-01")
+        .with_status(200)
+        .with_body(r#"<OAI-PMH><ListRecords>
+            <record><metadata><oai_dc:dc>
+              <dc:identifier>https://eprint.iacr.org/2024/1</dc:identifier>
+              <dc:title>New zero-knowledge proofs</dc:title>
+              <dc:date>2024-09-18T07:46:25Z</dc:date>
+            </oai_dc:dc></metadata></record>
+            <record><metadata><oai_dc:dc>
+              <dc:identifier>https://eprint.iacr.org/2023/2</dc:identifier>
+              <dc:title>Old zero-knowledge proofs</dc:title>
+              <dc:date>2023-03-01T10:00:00Z</dc:date>
+              <dc:date>2024-09-19T07:46:25Z</dc:date>
+            </oai_dc:dc></metadata></record>
+        </ListRecords></OAI-PMH>"#)
+        .create();
+
+    let mut config = NamedTempFile::new().expect("Failed to create temporary file");
+    config.write_all(br#"{"keywords": ["zero-knowledge"], "include_revisions": true}"#).expect("Failed to write config");
+
+    let blogs: Vec<BlogInfo> = serde_json::from_value(serde_json::json!([{
+        "name": "ePrint ZK",
+        "domain": format!("{}/revisions", mockito::server_url()),
+        "feed_type": "Eprint",
+        "eprint_config": config.path().to_str().unwrap()
+    }])).expect("Failed to build blogs");
+
+    let window = DateWindow::since(NaiveDate::from_ymd_opt(2024, 9, 1).unwrap());
+    let report = Aggregator::new(blogs).fetch(&window).await;
+
+    assert!(report.errors.is_empty());
+    assert_eq!(report.eprint_articles.len(), 1);
+    assert_eq!(report.eprint_articles[0].url, "https://eprint.iacr.org/2024/1");
+    assert_eq!(report.eprint_revisions.len(), 1);
+    assert_eq!(report.eprint_revisions[0].url, "https://eprint.iacr.org/2023/2");
+    assert_eq!(report.eprint_revisions[0].updated, Some(Utc.with_ymd_and_hms(2024, 9, 19, 7, 46, 25).unwrap()));
+}
+
+#[tokio::test]
+async fn test_aggregator_applies_top_n_per_section() {
+    let record = |id: &str, title: &str, dates: &[&str]| {
+        let dates: String = dates.iter().map(|date| format!("<dc:date>{}</dc:date>", date)).collect();
+        format!("<record><metadata><oai_dc:dc><dc:identifier>https://eprint.iacr.org/{}</dc:identifier><dc:title>{}</dc:title>{}</oai_dc:dc></metadata></record>", id, title, dates)
+    };
+    let _m = mock("GET", "/top-n?from=2024-09-01")
+        .with_status(200)
+        .with_body(format!("<OAI-PMH><ListRecords>{}</ListRecords></OAI-PMH>", [
+            // Revisions scoring best, which must not take the slots of new papers
+            record("2023/1", "Zero-knowledge homomorphic folding", &["2023-03-01T10:00:00Z", "2024-09-19T07:46:25Z"]),
+            record("2023/2", "Zero-knowledge homomorphic proofs", &["2023-03-01T10:00:00Z", "2024-09-19T07:46:25Z"]),
+            record("2023/3", "Zero-knowledge homomorphic lookups", &["2023-03-01T10:00:00Z", "2024-09-19T07:46:25Z"]),
+            record("2024/1", "Zero-knowledge homomorphic arguments", &["2024-09-18T07:46:25Z"]),
+            record("2024/2", "Zero-knowledge proofs", &["2024-09-18T07:46:25Z"]),
+            record("2024/3", "Homomorphic encryption", &["2024-09-18T07:46:25Z"]),
+            record("2024/4", "Zero-knowledge lookups", &["2024-09-20T07:46:25Z"]),
+        ].concat()))
+        .create();
+
+    let mut zk_config = NamedTempFile::new().expect("Failed to create temporary file");
+    zk_config.write_all(br#"{"keywords": ["zero-knowledge", "homomorphic"], "include_revisions": true, "scoring": {"top_n": 2}}"#).expect("Failed to write config");
+    let mut fhe_config = NamedTempFile::new().expect("Failed to create temporary file");
+    fhe_config.write_all(br#"{"keywords": ["homomorphic"], "include_revisions": true, "scoring": {"top_n": 1}}"#).expect("Failed to write config");
+
+    let domain = format!("{}/top-n", mockito::server_url());
+    let blogs: Vec<BlogInfo> = serde_json::from_value(serde_json::json!([
+        { "name": "ePrint ZK", "domain": domain, "feed_type": "Eprint", "eprint_config": zk_config.path().to_str().unwrap() },
+        { "name": "ePrint FHE", "domain": domain, "feed_type": "Eprint", "eprint_config": fhe_config.path().to_str().unwrap() }
+    ])).expect("Failed to build blogs");
+
+    let window = DateWindow::since(NaiveDate::from_ymd_opt(2024, 9, 1).unwrap());
+    let report = Aggregator::new(blogs).fetch(&window).await;
+
+    assert!(report.errors.is_empty(), "unexpected errors: {:?}", report.errors);
+    // Both profiles share the largest top_n, and a paper both select takes a single slot
+    let urls: Vec<&str> = report.eprint_articles.iter().map(|a| a.url.as_str()).collect();
+    assert_eq!(urls, vec!["https://eprint.iacr.org/2024/1", "https://eprint.iacr.org/2024/2"]);
+    let urls: Vec<&str> = report.eprint_revisions.iter().map(|a| a.url.as_str()).collect();
+    assert_eq!(urls, vec!["https://eprint.iacr.org/2023/1", "https://eprint.iacr.org/2023/2"]);
+}
@@ -0,0 +1,241 @@
+use crate::config::{load_eprint_config, DEFAULT_EPRINT_CONFIG_FILE};
+use crate::feed_types::{ArticleFetcher, ArxivFetcher, FetchContext};
+use crate::models::EprintConfig;
+use crate::dates::DateWindow;
+use chrono::{NaiveDate, TimeZone, Utc};
+use mockito::{mock, Matcher};
+
+fn entry(id: &str, published: &str, updated: &str, title: &str, authors: &[&str]) -> String {
+    let authors: String = authors.iter()
+        .map(|name| format!("<author><name>{}</name></author>", name))
+        .collect();
+    format!(r#"
+    <entry>
+      <id>http://arxiv.org/abs/{id}</id>
+      <updated>{updated}</updated>
+      <published>{published}</published>
+      <title>{title}</title>
+      <summary>  We present a new
+        proof system.</summary>
+      {authors}
+      <link href="http://arxiv.org/abs/{id}" rel="alternate" type="text/html"/>
+      <link title="pdf" href="http://arxiv.org/pdf/{id}" rel="related" type="application/pdf"/>
+      <category term="cs.CR" scheme="http://arxiv.org/schemas/atom"/>
+    </entry>"#)
+}
+
+fn page(entries: &[String]) -> String {
+    format!(r#"<?xml version="1.0" encoding="UTF-8"?>
+    <feed xmlns="http://www.w3.org/2005/Atom" xmlns:opensearch="http://a9.com/-/spec/opensearch/1.1/">
+      <id>http://arxiv.org/api/query</id>
+      <title>arXiv Query</title>
+      <updated>2024-09-27T00:00:00Z</updated>
+      <opensearch:itemsPerPage>2</opensearch:itemsPerPage>
+      {}
+    </feed>"#, entries.concat())
+}
+
+fn config(keywords: &[&str], authors: &[&str], categories: &[&str]) -> EprintConfig {
+    EprintConfig {
+        keywords: keywords.iter().map(|k| k.to_string()).collect(),
+        authors: authors.iter().map(|a| a.to_string()).collect(),
+        categories: categories.iter().map(|c| c.to_string()).collect(),
+        ..EprintConfig::default()
+    }
+}
+
+#[test]
+fn test_arxiv_search_query() {
+    let fetcher = ArxivFetcher::new(&config(&["zero knowledge", "stark", "-lattice"], &["Dan Boneh"], &["cs.CR", "cs.CC"])).unwrap();
+    assert_eq!(fetcher.search_query(), r#"(cat:cs.CR OR cat:cs.CC) AND (all:"zero knowledge" OR all:"stark" OR au:"Dan Boneh")"#);
+
+    let fetcher = ArxivFetcher::new(&config(&["snark"], &[], &[])).unwrap();
+    assert_eq!(fetcher.search_query(), r#"all:"snark""#);
+
+    // `*` endings become wildcards, regular expressions are only applied by the filter
+    let fetcher = ArxivFetcher::new(&config(&["zk*", "zero know*", "/zk-?snarks?/", "stark"], &["Dan Boneh"], &["cs.CR"])).unwrap();
+    assert_eq!(fetcher.search_query(), r#"cat:cs.CR AND (all:zk* OR (all:zero AND all:know*) OR all:"stark" OR au:"Dan Boneh")"#);
+
+    let fetcher = ArxivFetcher::new(&config(&["/zk-?snarks?/"], &[], &["cs.CR"])).unwrap();
+    assert_eq!(fetcher.search_query(), "cat:cs.CR");
+
+    let default_config = load_eprint_config(DEFAULT_EPRINT_CONFIG_FILE).unwrap();
+    let search_query = ArxivFetcher::new(&default_config).unwrap().search_query().to_string();
+    assert!(search_query.starts_with(r#"(cat:cs.CR OR cat:cs.CC) AND (all:"zero-knowledge" OR all:"zero knowledge" OR all:zk* OR all:snark* OR"#), "{}", search_query);
+    assert!(search_query.contains(r#"au:"Benedikt Buenz""#), "{}", search_query);
+
+    assert_eq!(ArxivFetcher::new(&config(&["zk*"], &[], &[])).unwrap().search_query(), "all:zk*");
+    assert!(ArxivFetcher::new(&config(&["/zk/"], &[], &[])).is_err());
+    assert!(ArxivFetcher::new(&config(&["zk(*"], &[], &["cs.CR"])).is_err());
+
+    // A quote would end the phrase early and break the query
+    let error = ArxivFetcher::new(&config(&["stark", "zero \"knowledge\""], &[], &["cs.CR"])).err().unwrap();
+    assert_eq!(error.to_string(), "Parse error: arXiv search term zero \"knowledge\" contains a double quote");
+    assert!(ArxivFetcher::new(&config(&["stark"], &["Dan \"DB\" Boneh"], &["cs.CR"])).is_err());
+}
+
+#[tokio::test]
+async fn test_fetch_arxiv_articles_follows_pages() {
+    let first_page = mock("GET", "/arxiv")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("search_query".into(), r#"cat:cs.CR AND (all:"stark" OR all:"zero-knowledge" OR au:"Dan Boneh")"#.into()),
+            Matcher::UrlEncoded("start".into(), "0".into()),
+            Matcher::UrlEncoded("max_results".into(), "2".into()),
+            Matcher::UrlEncoded("sortBy".into(), "submittedDate".into()),
+            Matcher::UrlEncoded("sortOrder".into(), "descending".into()),
+        ]))
+        .with_status(200)
+        .with_header("content-type", "application/atom+xml")
+        .with_body(page(&[
+            entry("2409.00003v2", "2024-09-20T10:00:00Z", "2024-09-25T10:00:00Z", "Folding\n  Schemes for STARK Provers", &["Alice Smith", "Dan Boneh"]),
+            entry("2409.00002v1", "2024-09-15T10:00:00Z", "2024-09-15T10:00:00Z", "Quantum Key Distribution", &["Bob Jones"]),
+        ]))
+        .expect(1)
+        .create();
+    let second_page = mock("GET", "/arxiv")
+        .match_query(Matcher::UrlEncoded("start".into(), "2".into()))
+        .with_status(200)
+        .with_body(page(&[
+            entry("2409.00001v1", "2024-09-02T10:00:00Z", "2024-09-02T10:00:00Z", "Zero-Knowledge Proofs", &["Carol White"]),
+            entry("2408.00009v1", "2024-08-30T10:00:00Z", "2024-08-30T10:00:00Z", "Older STARK paper", &["Dan Boneh"]),
+        ]))
+        .expect(1)
+        .create();
+    let third_page = mock("GET", "/arxiv")
+        .match_query(Matcher::UrlEncoded("start".into(), "4".into()))
+        .with_status(200)
+        .with_body(page(&[]))
+        .expect(0)
+        .create();
+
+    let fetcher = ArxivFetcher::new(&config(&["stark", "zero-knowledge"], &["Dan Boneh"], &["cs.CR"]))
+        .unwrap()
+        .with_page_size(2);
+    let window = DateWindow::since(NaiveDate::from_ymd_opt(2024, 9, 1).unwrap());
+    let feed_url = format!("{}/arxiv", mockito::server_url());
+    let articles = fetcher.fetch_articles(&FetchContext::default(), &feed_url, &window, "arXiv ZK", None)
+        .await
+        .expect("Failed to fetch arXiv articles");
+
+    first_page.assert();
+    second_page.assert();
+    third_page.assert();
+    assert_eq!(articles.len(), 2);
+    assert_eq!(articles[0].url, "http://arxiv.org/abs/2409.00003");
+    assert_eq!(articles[0].title, "Folding Schemes for STARK Provers");
+    assert_eq!(articles[0].authors, Some("Alice Smith and Dan Boneh".to_string()));
+    assert_eq!(articles[0].blog_name, "arXiv");
+    assert_eq!(articles[0].published, Utc.with_ymd_and_hms(2024, 9, 20, 10, 0, 0).unwrap());
+    assert_eq!(articles[0].updated, Some(Utc.with_ymd_and_hms(2024, 9, 25, 10, 0, 0).unwrap()));
+    assert_eq!(articles[0].score, Some(8.0));
+    assert_eq!(articles[1].url, "http://arxiv.org/abs/2409.00001");
+    assert_eq!(articles[1].authors, Some("Carol White".to_string()));
+    assert_eq!(articles[1].updated, None);
+}
+
+#[tokio::test]
+async fn test_fetch_arxiv_articles_skips_entries_without_date() {
+    let body = page(&[
+        entry("2409.00005v1", "2024-09-20T10:00:00Z", "2024-09-20T10:00:00Z", "STARK provers", &["Alice Smith"]),
+        entry("2409.00004v1", "2024-09-19T10:00:00Z", "2024-09-19T10:00:00Z", "STARK verifiers", &["Alice Smith"])
+            .replace("<published>2024-09-19T10:00:00Z</published>", ""),
+    ]);
+    let _m = mock("GET", "/arxiv-undated")
+        .match_query(Matcher::Any)
+        .with_status(200)
+        .with_body(body)
+        .create();
+
+    let fetcher = ArxivFetcher::new(&config(&["stark"], &[], &["cs.CR"])).unwrap();
+    let ctx = FetchContext::default();
+    let window = DateWindow::since(NaiveDate::from_ymd_opt(2024, 9, 1).unwrap());
+    let feed_url = format!("{}/arxiv-undated", mockito::server_url());
+    let articles = fetcher.fetch_articles(&ctx, &feed_url, &window, "arXiv ZK", None)
+        .await
+        .expect("Failed to fetch arXiv articles");
+
+    assert_eq!(articles.len(), 1);
+    assert_eq!(articles[0].url, "http://arxiv.org/abs/2409.00005");
+    let skipped = ctx.log.skipped();
+    assert_eq!(skipped.len(), 1);
+    assert_eq!(skipped[0].item, "http://arxiv.org/abs/2409.00004");
+    assert_eq!(skipped[0].reason, "Parse error: Entry has no published date");
+}
+
+#[tokio::test]
+async fn test_fetch_arxiv_articles_reports_api_error() {
+    let error = r#"
+    <entry>
+      <id>http://arxiv.org/api/errors#incorrect_id_format_for_1234.12345</id>
+      <title>Error</title>
+      <summary>incorrect id format for 1234.12345</summary>
+      <updated>2024-09-27T00:00:00-04:00</updated>
+      <link href="http://arxiv.org/api/errors#incorrect_id_format_for_1234.12345" rel="alternate" type="text/html"/>
+      <author><name>arXiv api core</name></author>
+    </entry>"#;
+    let _m = mock("GET", "/arxiv-error")
+        .match_query(Matcher::Any)
+        .with_status(200)
+        .with_body(page(&[error.to_string()]))
+        .create();
+
+    let fetcher = ArxivFetcher::new(&config(&["stark"], &[], &["cs.CR"])).unwrap();
+    let ctx = FetchContext::default();
+    let window = DateWindow::since(NaiveDate::from_ymd_opt(2024, 9, 1).unwrap());
+    let feed_url = format!("{}/arxiv-error", mockito::server_url());
+    let error = fetcher.fetch_articles(&ctx, &feed_url, &window, "arXiv ZK", None).await.unwrap_err();
+
+    assert_eq!(error.to_string(), "Parse error: arXiv rejected the query: incorrect id format for 1234.12345");
+    assert!(ctx.log.skipped().is_empty());
+}
+
+#[tokio::test]
+async fn test_fetch_arxiv_articles_until() {
+    let _m = mock("GET", "/arxiv-until")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("search_query".into(), r#"cat:cs.CR AND all:"stark" AND submittedDate:[202409010000 TO 202409152359]"#.into()),
+            Matcher::UrlEncoded("start".into(), "0".into()),
+        ]))
+        .with_status(200)
+        .with_body(page(&[
+            // arXiv only sends papers of the range, a later one is still left out
+            entry("2409.00003v1", "2024-09-20T10:00:00Z", "2024-09-20T10:00:00Z", "STARK Provers", &["Alice Smith"]),
+            entry("2409.00002v1", "2024-09-15T10:00:00Z", "2024-09-15T10:00:00Z", "STARK Folding", &["Bob Jones"]),
+        ]))
+        .create();
+
+    let fetcher = ArxivFetcher::new(&config(&["stark"], &[], &["cs.CR"])).unwrap();
+    let date = |day| NaiveDate::from_ymd_opt(2024, 9, day).unwrap();
+    let window = DateWindow::new(date(1), Some(date(15))).unwrap();
+    let feed_url = format!("{}/arxiv-until", mockito::server_url());
+    let articles = fetcher.fetch_articles(&FetchContext::default(), &feed_url, &window, "arXiv ZK", None)
+        .await
+        .expect("Failed to fetch arXiv articles");
+
+    let urls: Vec<_> = articles.iter().map(|a| a.url.as_str()).collect();
+    assert_eq!(urls, vec!["http://arxiv.org/abs/2409.00002"]);
+}
+
+#[tokio::test]
+async fn test_fetch_arxiv_articles_warns_at_page_cap() {
+    let pages = mock("GET", "/arxiv-capped")
+        .match_query(Matcher::Any)
+        .with_status(200)
+        .with_body(page(&[entry("2409.00006v1", "2024-09-20T10:00:00Z", "2024-09-20T10:00:00Z", "STARK provers", &["Alice Smith"])]))
+        .expect(2)
+        .create();
+
+    let fetcher = ArxivFetcher::new(&config(&["stark"], &[], &["cs.CR"]))
+        .unwrap()
+        .with_page_size(1)
+        .with_max_pages(2);
+    let ctx = FetchContext::default();
+    let window = DateWindow::since(NaiveDate::from_ymd_opt(2024, 9, 1).unwrap());
+    let feed_url = format!("{}/arxiv-capped", mockito::server_url());
+    fetcher.fetch_articles(&ctx, &feed_url, &window, "arXiv ZK", None)
+        .await
+        .expect("Failed to fetch arXiv articles");
+
+    pages.assert();
+    assert_eq!(ctx.log.warnings(), vec!["Stopped after 2 pages, older papers were not fetched"]);
+}
@@ -1,7 +1,7 @@
-use crate::feed_types::{ArticleFetcher, AtomFetcher};
-use chrono::NaiveDate;
+use crate::feed_types::{ArticleFetcher, FetchContext, AtomFetcher};
+use crate::dates::DateWindow;
+use chrono::{NaiveDate, TimeZone, Utc};
 use mockito::mock;
-use tokio;
 
 #[tokio::test]
 async fn test_fetch_atom_articles() {
@@ -24,14 +24,63 @@ async fn test_fetch_atom_articles() {
         .with_body(mock_response)
         .create();
 
-    let since_date = NaiveDate::from_ymd_opt(2024, 9, 1).unwrap();
+    let window = DateWindow::since(NaiveDate::from_ymd_opt(2024, 9, 1).unwrap());
     let fetcher = AtomFetcher;
-    let articles = fetcher.fetch_articles(&mockito::server_url(), &since_date, "TestAtomBlog", None)
+    let articles = fetcher.fetch_articles(&FetchContext::default(), &mockito::server_url(), &window, "TestAtomBlog", None)
         .await
         .expect("Failed to fetch articles");
 
     assert_eq!(articles.len(), 1);
     assert_eq!(articles[0].title, "Test Atom Article");
     assert_eq!(articles[0].url, "https://test.com/atom-article");
-    assert_eq!(articles[0].date, NaiveDate::from_ymd_opt(2024, 10, 1).unwrap());
-}
\ No newline at end of file
+    assert_eq!(articles[0].published, Utc.with_ymd_and_hms(2024, 10, 1, 12, 0, 0).unwrap());
+}
+#[tokio::test]
+async fn test_fetch_atom_articles_by_publication_date() {
+    let mock_response = r#"<?xml version="1.0" encoding="utf-8"?>
+    <feed xmlns="http://www.w3.org/2005/Atom">
+        <author><name>Feed Author</name></author>
+        <entry>
+            <title>Edited Old Article</title>
+            <link rel="alternate" href="https://test.com/old"/>
+            <published>2024-06-01T12:00:00Z</published>
+            <updated>2024-10-02T12:00:00Z</updated>
+        </entry>
+        <entry>
+            <title>Edited New Article</title>
+            <link rel="self" href="https://test.com/feed/new"/>
+            <link rel="alternate" type="text/html" href="/posts/new"/>
+            <published>2024-10-01T12:00:00Z</published>
+            <updated>2024-10-03T12:00:00Z</updated>
+            <author><name>Alice Smith</name></author>
+            <author><name>Bob Jones</name></author>
+        </entry>
+        <entry>
+            <title>Undated Update</title>
+            <link href="https://test.com/updated-only"/>
+            <updated>2024-10-04T12:00:00Z</updated>
+        </entry>
+    </feed>"#;
+
+    let _m = mock("GET", "/atom/published.xml")
+        .with_status(200)
+        .with_body(mock_response)
+        .create();
+
+    let window = DateWindow::since(NaiveDate::from_ymd_opt(2024, 9, 1).unwrap());
+    let feed_url = format!("{}/atom/published.xml", mockito::server_url());
+    let articles = AtomFetcher.fetch_articles(&FetchContext::default(), &feed_url, &window, "TestAtomBlog", None)
+        .await
+        .expect("Failed to fetch articles");
+
+    assert_eq!(articles.len(), 2);
+    assert_eq!(articles[0].title, "Edited New Article");
+    assert_eq!(articles[0].url, format!("{}/posts/new", mockito::server_url()));
+    assert_eq!(articles[0].published, Utc.with_ymd_and_hms(2024, 10, 1, 12, 0, 0).unwrap());
+    assert_eq!(articles[0].updated, Some(Utc.with_ymd_and_hms(2024, 10, 3, 12, 0, 0).unwrap()));
+    assert_eq!(articles[0].authors, Some("Alice Smith and Bob Jones".to_string()));
+    assert_eq!(articles[1].url, "https://test.com/updated-only");
+    assert_eq!(articles[1].published, Utc.with_ymd_and_hms(2024, 10, 4, 12, 0, 0).unwrap());
+    assert_eq!(articles[1].updated, None);
+    assert_eq!(articles[1].authors, Some("Feed Author".to_string()));
+}
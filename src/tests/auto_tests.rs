@@ -0,0 +1,181 @@
+use crate::feed_types::{detect_feed, DetectedFeed, FeedType, FetchContext, FetcherRegistry, FromBlogInfo, RssFetcher};
+use crate::models::BlogInfo;
+use crate::dates::DateWindow;
+use chrono::NaiveDate;
+use mockito::mock;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+const RSS_FEED: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0">
+    <channel>
+        <item>
+            <title>Detected RSS Article</title>
+            <link>https://test.com/detected</link>
+            <pubDate>Tue, 01 Oct 2024 12:00:00 GMT</pubDate>
+        </item>
+    </channel>
+</rss>"#;
+
+async fn detect(path: &str, content_type: &str, body: &str) -> DetectedFeed {
+    let _m = mock("GET", path)
+        .with_status(200)
+        .with_header("content-type", content_type)
+        .with_body(body)
+        .create();
+    let url = format!("{}{}", mockito::server_url(), path);
+    detect_feed(&FetchContext::default(), &url).await.expect("Failed to detect feed")
+}
+
+#[tokio::test]
+async fn test_detect_feed_by_root_element() {
+    let detected = detect("/detect/rss", "text/xml", RSS_FEED).await;
+    assert_eq!(detected.feed_type, FeedType::RSS);
+    assert_eq!(detected.feed_url, format!("{}/detect/rss", mockito::server_url()));
+
+    let atom = r#"<?xml version="1.0"?><!-- comment --><feed xmlns="http://www.w3.org/2005/Atom"><title>T</title></feed>"#;
+    assert_eq!(detect("/detect/atom", "application/xml", atom).await.feed_type, FeedType::Atom);
+
+    let rdf = r#"<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#" xmlns="http://purl.org/rss/1.0/"></rdf:RDF>"#;
+    assert_eq!(detect("/detect/rdf", "application/rdf+xml", rdf).await.feed_type, FeedType::RSS);
+
+    let oai = r#"<?xml version="1.0"?><OAI-PMH xmlns="http://www.openarchives.org/OAI/2.0/"></OAI-PMH>"#;
+    assert_eq!(detect("/detect/oai", "text/xml", oai).await.feed_type, FeedType::Eprint);
+}
+
+#[tokio::test]
+async fn test_detect_feed_json() {
+    let json_feed = r#"{"version": "https://jsonfeed.org/version/1.1", "title": "T", "items": []}"#;
+    assert_eq!(detect("/detect/json-feed", "application/feed+json", json_feed).await.feed_type, FeedType::JsonFeed);
+
+    let substack = r#"[{"title": "Post", "slug": "post", "post_date": "2024-10-01T12:00:00.000Z"}]"#;
+    assert_eq!(detect("/detect/substack-api", "application/json", substack).await.feed_type, FeedType::Substack);
+}
+
+#[tokio::test]
+async fn test_detect_feed_follows_alternate_link() {
+    let _feed = mock("GET", "/discovered/feed.xml")
+        .with_status(200)
+        .with_header("content-type", "application/rss+xml")
+        .with_body(RSS_FEED)
+        .create();
+    let page = r#"<!DOCTYPE html>
+    <html><head>
+        <title> Example Blog </title>
+        <link rel="stylesheet" href="/style.css">
+        <link rel="alternate" type="application/rss+xml" title="Feed" href="feed.xml">
+    </head><body></body></html>"#;
+
+    let detected = detect("/discovered/", "text/html; charset=utf-8", page).await;
+
+    assert_eq!(detected.feed_type, FeedType::RSS);
+    assert_eq!(detected.feed_url, format!("{}/discovered/feed.xml", mockito::server_url()));
+    assert_eq!(detected.title, Some("Example Blog".to_string()));
+    let blog = serde_json::to_value(detected.blog_info()).unwrap();
+    assert_eq!(blog, serde_json::json!({
+        "name": "Example Blog",
+        "domain": format!("{}/discovered/feed.xml", mockito::server_url()),
+        "feed_type": "RSS"
+    }));
+}
+
+#[tokio::test]
+async fn test_detect_feed_html_pages() {
+    // The feed link is broken, so the generator tells to use the posts API
+    let substack = r#"<html><head><title>Newsletter</title>
+        <meta name="generator" content="Substack">
+        <link rel="alternate" type="application/rss+xml" href="/detect/newsletter/missing-feed"></head></html>"#;
+    let detected = detect("/detect/newsletter", "text/html", substack).await;
+    assert_eq!(detected.feed_type, FeedType::Substack);
+    assert_eq!(detected.feed_url, format!("{}/api/v1/posts/?limit=50", mockito::server_url()));
+
+    let no_feed = "<html><head><title>Plain Blog</title></head><body><h1>Posts</h1></body></html>";
+    let detected = detect("/detect/plain", "text/html", no_feed).await;
+    assert_eq!(detected.feed_type, FeedType::CustomHTML);
+    let blog = detected.blog_info();
+    assert_eq!(blog.name, "Plain Blog");
+    assert!(blog.custom_selectors.is_some());
+
+    let _m = mock("GET", "/detect/unknown").with_status(200).with_body("plain text").create();
+    let result = detect_feed(&FetchContext::default(), &format!("{}/detect/unknown", mockito::server_url())).await;
+    assert!(result.unwrap_err().to_string().starts_with("Parse error: Unrecognised content"));
+}
+
+#[tokio::test]
+async fn test_detect_feed_ignores_embedded_substack_cdn() {
+    let _feed = mock("GET", "/embeds/feed.xml")
+        .with_status(200)
+        .with_body(RSS_FEED)
+        .create();
+    let page = r#"<html><head><title>Embeds</title>
+        <link rel="alternate" type="application/rss+xml" href="/embeds/feed.xml"></head>
+        <body><img src="https://substackcdn.com/image/fetch/post.png"></body></html>"#;
+    let detected = detect("/embeds/", "text/html", page).await;
+    assert_eq!(detected.feed_type, FeedType::RSS);
+    assert_eq!(detected.feed_url, format!("{}/embeds/feed.xml", mockito::server_url()));
+
+    let no_feed = r#"<html><head><title>Embeds</title></head>
+        <body><img src="https://substackcdn.com/image/fetch/post.png"></body></html>"#;
+    assert_eq!(detect("/embeds/no-feed", "text/html", no_feed).await.feed_type, FeedType::CustomHTML);
+}
+
+#[tokio::test]
+async fn test_auto_fetcher_uses_detected_fetcher() {
+    let _page = mock("GET", "/auto/")
+        .with_status(200)
+        .with_header("content-type", "text/html")
+        .with_body(r#"<html><head><link rel="alternate" type="application/rss+xml" href="/auto/rss"></head></html>"#)
+        .create();
+    // Detection downloads the feed, the RSS fetcher reads that response
+    let feed = mock("GET", "/auto/rss")
+        .with_status(200)
+        .with_body(RSS_FEED)
+        .expect(1)
+        .create();
+
+    let blog: BlogInfo = serde_json::from_value(serde_json::json!({
+        "name": "Auto Blog",
+        "domain": format!("{}/auto/", mockito::server_url()),
+        "feed_type": "Auto"
+    })).unwrap();
+    let fetcher = FetcherRegistry::default().build(&blog).expect("Failed to build the Auto fetcher");
+    let window = DateWindow::since(NaiveDate::from_ymd_opt(2024, 9, 1).unwrap());
+    let articles = fetcher.fetch_articles(&FetchContext::default(), &blog.domain, &window, &blog.name, None)
+        .await
+        .expect("Failed to fetch articles");
+
+    assert_eq!(articles.len(), 1);
+    assert_eq!(articles[0].url, "https://test.com/detected");
+    assert_eq!(articles[0].blog_name, "Auto Blog");
+    feed.assert();
+}
+
+#[tokio::test]
+async fn test_auto_fetcher_builds_from_context_registry() {
+    let _feed = mock("GET", "/auto-registry/rss")
+        .with_status(200)
+        .with_body(RSS_FEED)
+        .create();
+    let built = Arc::new(AtomicUsize::new(0));
+    let mut registry = FetcherRegistry::default();
+    let counter = built.clone();
+    registry.register("RSS", move |blog| {
+        counter.fetch_add(1, Ordering::SeqCst);
+        Ok(Box::new(RssFetcher::from_blog_info(blog)?))
+    });
+    let ctx = FetchContext { registry: Arc::new(registry), ..FetchContext::default() };
+
+    let blog: BlogInfo = serde_json::from_value(serde_json::json!({
+        "name": "Auto Blog",
+        "domain": format!("{}/auto-registry/rss", mockito::server_url()),
+        "feed_type": "Auto"
+    })).unwrap();
+    let fetcher = ctx.registry.build(&blog).expect("Failed to build the Auto fetcher");
+    let window = DateWindow::since(NaiveDate::from_ymd_opt(2024, 9, 1).unwrap());
+    let articles = fetcher.fetch_articles(&ctx, &blog.domain, &window, &blog.name, None)
+        .await
+        .expect("Failed to fetch articles");
+
+    assert_eq!(articles.len(), 1);
+    assert_eq!(built.load(Ordering::SeqCst), 1);
+}
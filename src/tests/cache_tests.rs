@@ -0,0 +1,86 @@
+use crate::cache::HttpCache;
+use crate::feed_types::{ArticleFetcher, FetchContext, RssFetcher};
+use crate::dates::DateWindow;
+use chrono::NaiveDate;
+use mockito::{mock, Matcher};
+use tempfile::tempdir;
+
+const RSS_BODY: &str = r#"
+<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0">
+    <channel>
+        <item>
+            <title>Cached RSS Article</title>
+            <link>https://test.com/cached-article</link>
+            <pubDate>Tue, 01 Oct 2024 12:00:00 GMT</pubDate>
+        </item>
+    </channel>
+</rss>
+"#;
+
+#[tokio::test]
+async fn test_not_modified_reuses_cached_body() {
+    let dir = tempdir().expect("Failed to create temporary directory");
+    let ctx = FetchContext {
+        cache: Some(HttpCache::new(dir.path())),
+        ..FetchContext::default()
+    };
+    let url = format!("{}/cached.xml", mockito::server_url());
+    let window = DateWindow::since(NaiveDate::from_ymd_opt(2024, 9, 1).unwrap());
+
+    let first = mock("GET", "/cached.xml")
+        .match_header("if-none-match", Matcher::Missing)
+        .with_status(200)
+        .with_header("etag", "\"v1\"")
+        .with_header("last-modified", "Tue, 01 Oct 2024 12:00:00 GMT")
+        .with_body(RSS_BODY)
+        .expect(1)
+        .create();
+    let articles = RssFetcher.fetch_articles(&ctx, &url, &window, "TestRSSBlog", None)
+        .await
+        .expect("Failed to fetch RSS articles");
+    assert_eq!(articles.len(), 1);
+    first.assert();
+
+    let revalidated = mock("GET", "/cached.xml")
+        .match_header("if-none-match", "\"v1\"")
+        .match_header("if-modified-since", "Tue, 01 Oct 2024 12:00:00 GMT")
+        .with_status(304)
+        .expect(1)
+        .create();
+    let articles = RssFetcher.fetch_articles(&ctx, &url, &window, "TestRSSBlog", None)
+        .await
+        .expect("Failed to fetch RSS articles from cache");
+    revalidated.assert();
+
+    assert_eq!(articles.len(), 1);
+    assert_eq!(articles[0].title, "Cached RSS Article");
+    let attempts = ctx.log.attempts();
+    assert_eq!(attempts.last().unwrap().outcome, "304 Not Modified");
+}
+
+#[tokio::test]
+async fn test_responses_without_validators_are_not_cached() {
+    let dir = tempdir().expect("Failed to create temporary directory");
+    let cache = HttpCache::new(dir.path());
+    let ctx = FetchContext {
+        cache: Some(cache.clone()),
+        ..FetchContext::default()
+    };
+    let url = format!("{}/uncached", mockito::server_url());
+
+    let _m = mock("GET", "/uncached")
+        .with_status(200)
+        .with_body("fresh")
+        .create();
+    assert_eq!(ctx.get_text(&url).await.expect("Failed to fetch"), "fresh");
+    assert!(cache.conditional_headers(&url).is_none());
+    assert_eq!(std::fs::read_dir(dir.path()).map_or(0, |entries| entries.count()), 0);
+}
+
+#[test]
+fn test_cache_missing_entry() {
+    let dir = tempdir().expect("Failed to create temporary directory");
+    let cache = HttpCache::new(dir.path());
+    assert!(cache.conditional_headers("https://test.com/feed").is_none());
+}
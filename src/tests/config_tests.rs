@@ -1,8 +1,9 @@
-use crate::config::read_blogs_from_file;
+use crate::config::{read_blogs_from_file, read_settings_from_file};
 use crate::feed_types::FeedType;
+use crate::rate_limit::HostLimit;
+use crate::utils::DEFAULT_SETTINGS_FILE;
 use std::fs::File;
 use tempfile::NamedTempFile;
-use serde_json;
 
 #[test]
 fn test_read_blogs_from_file() {
@@ -57,4 +58,36 @@ fn test_read_blogs_from_file() {
     assert_eq!(custom_selectors.url_selector, ".url");
     assert_eq!(custom_selectors.date_selector, ".date");
     assert_eq!(custom_selectors.date_format, "%Y-%m-%d");
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_read_settings_from_file() {
+    let temp_file = NamedTempFile::new().expect("Failed to create temporary file");
+    let path = temp_file.path().to_str().expect("Failed to get path as string");
+
+    let file = File::create(path).expect("Failed to create file");
+    let json_content = serde_json::json!({
+        "http": {
+            "timeout_secs": 5,
+            "user_agent": "TestAgent/1.0",
+            "proxy": "http://proxy.local:3128"
+        }
+    });
+    serde_json::to_writer_pretty(file, &json_content).expect("Failed to write JSON to file");
+
+    let settings = read_settings_from_file(path).unwrap();
+
+    assert_eq!(settings.http.timeout_secs, 5);
+    assert_eq!(settings.http.user_agent, "TestAgent/1.0");
+    assert_eq!(settings.http.proxy, Some("http://proxy.local:3128".to_string()));
+    // Missing fields keep their defaults
+    assert_eq!(settings.http.max_redirects, 10);
+}
+
+#[test]
+fn test_shipped_settings_throttle_arxiv() {
+    let settings = read_settings_from_file(DEFAULT_SETTINGS_FILE).unwrap();
+
+    let arxiv = settings.rate_limit.hosts["export.arxiv.org"];
+    assert_eq!(arxiv, HostLimit::Bucket { requests_per_second: 0.33, burst: Some(1) });
+}
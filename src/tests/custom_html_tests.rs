@@ -1,7 +1,7 @@
-use crate::feed_types::{ArticleFetcher, CustomHtmlFetcher};
-use chrono::NaiveDate;
+use crate::feed_types::{ArticleFetcher, FetchContext, CustomHtmlFetcher};
+use crate::dates::DateWindow;
+use chrono::{NaiveDate, TimeZone, Utc};
 use mockito::mock;
-use tokio;
 
 #[tokio::test]
 async fn test_fetch_custom_html_articles() {
@@ -26,7 +26,7 @@ async fn test_fetch_custom_html_articles() {
         .with_body(mock_response)
         .create();
 
-    let since_date = NaiveDate::from_ymd_opt(2024, 9, 1).unwrap();
+    let window = DateWindow::since(NaiveDate::from_ymd_opt(2024, 9, 1).unwrap());
     let fetcher = CustomHtmlFetcher {
         article_selector: ".blog-list_wrapper.w-dyn-list".to_string(),
         article_item_selector: ".blog-list_item-wrapper.w-dyn-item".to_string(),
@@ -36,12 +36,55 @@ async fn test_fetch_custom_html_articles() {
         date_format: "%B %d, %Y".to_string()
     };
 
-    let articles = fetcher.fetch_articles(&mockito::server_url(), &since_date, "TestPolygonBlog", None)
+    let articles = fetcher.fetch_articles(&FetchContext::default(), &mockito::server_url(), &window, "TestPolygonBlog", None)
         .await
         .expect("Failed to fetch articles");
 
     assert_eq!(articles.len(), 1);
     assert_eq!(articles[0].title, "Test Polygon Article");
     assert_eq!(articles[0].url, format!("{}{}", &mockito::server_url(), "/blog/test-article"));
-    assert_eq!(articles[0].date, NaiveDate::from_ymd_opt(2024, 10, 1).unwrap());
-}
\ No newline at end of file
+    assert_eq!(articles[0].published, Utc.with_ymd_and_hms(2024, 10, 1, 0, 0, 0).unwrap());
+}
+
+#[tokio::test]
+async fn test_fetch_custom_html_skips_bad_items() {
+    let mock_response = r#"
+    <html>
+        <body>
+            <ul class="posts">
+                <li class="post"><a href="/blog/good"><h3>Good Article</h3><span>October 1, 2024</span></a></li>
+                <li class="post"><a href="/blog/undated"><h3>Undated Article</h3></a></li>
+                <li class="post"><a href="/blog/misdated"><h3>Misdated Article</h3><span>Autumn 2024</span></a></li>
+            </ul>
+        </body>
+    </html>
+    "#;
+
+    let _m = mock("GET", "/partly-broken.html")
+        .with_status(200)
+        .with_header("content-type", "text/html")
+        .with_body(mock_response)
+        .create();
+
+    let ctx = FetchContext::default();
+    let window = DateWindow::since(NaiveDate::from_ymd_opt(2024, 9, 1).unwrap());
+    let fetcher = CustomHtmlFetcher {
+        article_selector: ".posts".to_string(),
+        article_item_selector: ".post".to_string(),
+        title_selector: "h3".to_string(),
+        url_selector: "a".to_string(),
+        date_selector: "span".to_string(),
+        date_format: "%B %d, %Y".to_string()
+    };
+    let feed_url = format!("{}/partly-broken.html", mockito::server_url());
+    let articles = fetcher.fetch_articles(&ctx, &feed_url, &window, "TestBlog", None)
+        .await
+        .expect("Failed to fetch articles");
+
+    assert_eq!(articles.len(), 1);
+    assert_eq!(articles[0].title, "Good Article");
+    let skipped = ctx.log.skipped();
+    assert_eq!(skipped.len(), 2);
+    assert_eq!(skipped[0].item, "Undated Article");
+    assert_eq!(skipped[1].item, "Misdated Article");
+}
@@ -0,0 +1,113 @@
+use crate::dates::{parse_datetime, report_date, start_of_day, DateWindow};
+use crate::feed_types::{ArticleFetcher, FetchContext, RssFetcher};
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use chrono_tz::Tz;
+use mockito::mock;
+
+fn utc(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(year, month, day, hour, minute, 0).unwrap()
+}
+
+#[test]
+fn test_parse_rfc3339_and_rfc2822_dates() {
+    let noon = utc(2024, 10, 1, 12, 0);
+    assert_eq!(parse_datetime("2024-10-01T12:00:00+00:00", Tz::UTC).unwrap(), noon);
+    assert_eq!(parse_datetime("2024-10-01T12:00:00.000Z", Tz::UTC).unwrap(), noon);
+    assert_eq!(parse_datetime("2024-10-01T14:00:00+02:00", Tz::UTC).unwrap(), noon);
+    assert_eq!(parse_datetime("Tue, 01 Oct 2024 12:00:00 GMT", Tz::UTC).unwrap(), noon);
+    assert_eq!(parse_datetime("Tue, 01 Oct 2024 14:00:00 +0200", Tz::UTC).unwrap(), noon);
+    assert_eq!(parse_datetime(" Tue, 01 Oct 2024 08:00:00 EDT ", Tz::UTC).unwrap(), noon);
+}
+
+#[test]
+fn test_parse_rfc822_variants() {
+    let noon = utc(2024, 10, 1, 12, 0);
+    assert_eq!(parse_datetime("1 Oct 2024 12:00:00 +0000", Tz::UTC).unwrap(), noon);
+    assert_eq!(parse_datetime("Tue, 1 Oct 2024 12:00 GMT", Tz::UTC).unwrap(), noon);
+    assert_eq!(parse_datetime("Tue, 01 Oct 24 12:00:00 UTC", Tz::UTC).unwrap(), noon);
+    assert_eq!(parse_datetime("Tuesday, 01 October 2024 14:00:00 CEST", Tz::UTC).unwrap(), noon);
+    assert_eq!(parse_datetime("Wed, 01 Oct 2024 12:00:00 +00:00", Tz::UTC).unwrap(), noon);
+    // Without a zone, the date is read in the reporting timezone
+    assert_eq!(parse_datetime("Tue, 01 Oct 2024 14:00:00", Tz::Europe__Paris).unwrap(), noon);
+}
+
+#[test]
+fn test_parse_iso8601_dates() {
+    let noon = utc(2024, 10, 1, 12, 0);
+    assert_eq!(parse_datetime("2024-10-01T12:00:00+0000", Tz::UTC).unwrap(), noon);
+    assert_eq!(parse_datetime("2024-10-01 12:00:00Z", Tz::UTC).unwrap(), noon);
+    assert_eq!(parse_datetime("2024-10-01T12:00Z", Tz::UTC).unwrap(), noon);
+    assert_eq!(parse_datetime("20241001T120000Z", Tz::UTC).unwrap(), noon);
+    assert_eq!(parse_datetime("2024-10-01T12:00:00", Tz::UTC).unwrap(), noon);
+    assert_eq!(parse_datetime("2024-10-01 08:00", Tz::America__New_York).unwrap(), noon);
+    assert_eq!(parse_datetime(" 2024-10-01 ", Tz::UTC).unwrap(), utc(2024, 10, 1, 0, 0));
+    assert_eq!(parse_datetime("2024-10-01", Tz::Europe__Paris).unwrap(), utc(2024, 9, 30, 22, 0));
+}
+
+#[test]
+fn test_parse_hour_only_and_gmt_offsets() {
+    let noon = utc(2024, 10, 1, 12, 0);
+    assert_eq!(parse_datetime("2024-10-01T14:00:00+02", Tz::UTC).unwrap(), noon);
+    assert_eq!(parse_datetime("2024-10-01T07:00-05", Tz::UTC).unwrap(), noon);
+    assert_eq!(parse_datetime("2024-10-01 14:00:00 GMT+2", Tz::UTC).unwrap(), noon);
+    assert_eq!(parse_datetime("2024-10-01 12:00:00 UTC", Tz::Europe__Paris).unwrap(), noon);
+    assert_eq!(parse_datetime("Tue, 01 Oct 2024 14:00:00 GMT+2", Tz::UTC).unwrap(), noon);
+    assert_eq!(parse_datetime("Tue, 01 Oct 2024 06:30:00 UTC-05:30", Tz::UTC).unwrap(), noon);
+    assert_eq!(parse_datetime("Tue, 01 Oct 2024 14:00:00 gmt+02", Tz::UTC).unwrap(), noon);
+    assert_eq!(parse_datetime("Tue, 01 Oct 2024 14:00:00 +02", Tz::UTC).unwrap(), noon);
+}
+
+#[test]
+fn test_parse_invalid_dates() {
+    for value in ["Invalid Date", "yesterday", "", "32 Oct 2024 12:00:00 GMT", "01 Oct 2024 12:00:00 XYZ", "2024-13-01"] {
+        let error = parse_datetime(value, Tz::UTC).unwrap_err();
+        assert_eq!(error.to_string(), format!("Parse error: Unable to parse date: {}", value.trim()));
+    }
+    for value in ["2024-10-01T12:00:00+200", "Tue, 01 Oct 2024 12:00:00 GMT+2:7", "Tue, 01 Oct 2024 12:00:00 GMT+02:60", "Tue, 01 Oct 2024 12:00:00 GMTX2"] {
+        let error = parse_datetime(value, Tz::UTC).unwrap_err();
+        assert_eq!(error.to_string(), format!("Parse error: Unable to parse date: {}", value.trim()));
+    }
+}
+
+#[test]
+fn test_report_date_and_start_of_day() {
+    let late = utc(2024, 9, 30, 23, 30);
+    assert_eq!(report_date(late, Tz::UTC), NaiveDate::from_ymd_opt(2024, 9, 30).unwrap());
+    assert_eq!(report_date(late, Tz::Europe__Paris), NaiveDate::from_ymd_opt(2024, 10, 1).unwrap());
+    assert_eq!(report_date(late, Tz::America__New_York), NaiveDate::from_ymd_opt(2024, 9, 30).unwrap());
+
+    let day = NaiveDate::from_ymd_opt(2024, 10, 1).unwrap();
+    assert_eq!(start_of_day(day, Tz::UTC), utc(2024, 10, 1, 0, 0));
+    assert_eq!(start_of_day(day, Tz::Europe__Paris), utc(2024, 9, 30, 22, 0));
+}
+
+#[tokio::test]
+async fn test_since_date_compared_in_reporting_timezone() {
+    let mock_response = r#"<rss version="2.0"><channel>
+        <item>
+            <title>Late Article</title>
+            <link>https://test.com/late</link>
+            <pubDate>Mon, 30 Sep 2024 23:30:00 +0000</pubDate>
+        </item>
+    </channel></rss>"#;
+
+    let _m = mock("GET", "/timezone.xml")
+        .with_status(200)
+        .with_body(mock_response)
+        .create();
+
+    let window = DateWindow::since(NaiveDate::from_ymd_opt(2024, 10, 1).unwrap());
+    let feed_url = format!("{}/timezone.xml", mockito::server_url());
+
+    let articles = RssFetcher.fetch_articles(&FetchContext::default(), &feed_url, &window, "TestRSSBlog", None)
+        .await
+        .expect("Failed to fetch RSS articles");
+    assert!(articles.is_empty());
+
+    let ctx = FetchContext { timezone: Tz::Europe__Paris, ..FetchContext::default() };
+    let articles = RssFetcher.fetch_articles(&ctx, &feed_url, &window, "TestRSSBlog", None)
+        .await
+        .expect("Failed to fetch RSS articles");
+    assert_eq!(articles.len(), 1);
+    assert_eq!(articles[0].published, utc(2024, 9, 30, 23, 30));
+}
@@ -1,7 +1,17 @@
-use crate::feed_types::{ArticleFetcher, EprintFetcher};
-use chrono::NaiveDate;
-use mockito::mock;
-use tokio;
+use crate::config::{load_eprint_config, DEFAULT_EPRINT_CONFIG_FILE};
+use crate::errors::AppError;
+use crate::feed_types::{ArticleFetcher, FetchContext, EprintFetcher, FromBlogInfo};
+use crate::models::{BlogInfo, EprintConfig};
+use crate::dates::DateWindow;
+use chrono::{NaiveDate, TimeZone, Utc};
+use mockito::{mock, Matcher};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+fn fetcher() -> EprintFetcher {
+    let config = load_eprint_config(DEFAULT_EPRINT_CONFIG_FILE).expect("Failed to load the default ePrint config");
+    EprintFetcher::new(&config).expect("Failed to compile the default ePrint config")
+}
 
 #[tokio::test]
 async fn test_fetch_eprint_articles() {
@@ -85,39 +95,42 @@ async fn test_fetch_eprint_articles() {
     </OAI-PMH>
     "#;
 
-    let _m = mock("GET", "/")
+    let _m = mock("GET", "/?from=2024-09-01")
         .with_status(200)
         .with_header("content-type", "application/xml")
         .with_body(mock_response)
         .create();
 
     // Set a date for filtering articles using from_ymd_opt
-    let since_date = NaiveDate::from_ymd_opt(2024, 9, 1)
-        .expect("Invalid date provided"); // Handle the case where the date is invalid
+    let window = DateWindow::since(NaiveDate::from_ymd_opt(2024, 9, 1)
+        .expect("Invalid date provided")); // Handle the case where the date is invalid
 
-    let fetcher = EprintFetcher;
-    let articles = fetcher.fetch_articles(&mockito::server_url(), &since_date, "TestEprintBlog", None)
+    let fetcher = fetcher();
+    let articles = fetcher.fetch_articles(&FetchContext::default(), &mockito::server_url(), &window, "TestEprintBlog", None)
         .await
         .expect("Failed to fetch Eprint articles");
 
+    // Papers come best first
     assert_eq!(articles.len(), 3);
     assert_eq!(articles[0].title, "Interactive Line-Point Zero-Knowledge with Sublinear Communication and Linear Computation");
     assert_eq!(articles[0].url, "https://eprint.iacr.org/2024/1431");
     assert_eq!(articles[0].blog_name, "Eprint");
     assert_eq!(articles[0].authors, Some("Fuchun Lin, Chaoping Xing and Yizhou Yao".to_string()));
-    assert_eq!(articles[1].title, "Another");
-    assert_eq!(articles[1].url, "https://eprint.iacr.org/2024/1234");
+    assert_eq!(articles[1].title, "Another title");
+    assert_eq!(articles[1].url, "https://eprint.iacr.org/2024/666");
     assert_eq!(articles[1].blog_name, "Eprint");
-    assert_eq!(articles[1].authors, Some("Author 1 and Author 2".to_string()));
-    assert_eq!(articles[2].title, "Another title");
-    assert_eq!(articles[2].url, "https://eprint.iacr.org/2024/666");
+    assert_eq!(articles[1].authors, Some("Solo Author".to_string()));
+    assert_eq!(articles[2].title, "Another");
+    assert_eq!(articles[2].url, "https://eprint.iacr.org/2024/1234");
     assert_eq!(articles[2].blog_name, "Eprint");
-    assert_eq!(articles[2].authors, Some("Solo Author".to_string()));    
+    assert_eq!(articles[2].authors, Some("Author 1 and Author 2".to_string()));
+    assert_eq!(articles[0].matched_rule, Some("keyword \"zero-knowledge\" in title, keyword \"zero-knowledge\" in description, keyword \"zk*\" in description".to_string()));
+    assert_eq!(articles[0].score, Some(5.0));
+    assert_eq!(articles[1].matched_rule, Some("keyword \"zero-knowledge\" in subject".to_string()));
+    assert_eq!(articles[2].score, Some(1.0));
 }
 
-#[tokio::test]
-async fn test_fetch_eprint_articles_with_filtering() {
-    let mock_response = r#"
+const FILTERING_RESPONSE: &str = r#"
     <?xml version="1.0" encoding="UTF-8"?> 
     <OAI-PMH xmlns="http://www.openarchives.org/OAI/2.0/">
       <ListRecords>
@@ -171,22 +184,85 @@ async fn test_fetch_eprint_articles_with_filtering() {
     </OAI-PMH>
     "#;
 
-    let _m = mock("GET", "/")
+#[tokio::test]
+async fn test_fetch_eprint_articles_with_filtering() {
+
+    let _m = mock("GET", "/?from=2024-09-01")
         .with_status(200)
         .with_header("content-type", "application/xml")
-        .with_body(mock_response)
+        .with_body(FILTERING_RESPONSE)
         .create();
 
-    let since_date = NaiveDate::from_ymd_opt(2024, 9, 1).unwrap();
-    let fetcher = EprintFetcher;
-    let articles = fetcher.fetch_articles(&mockito::server_url(), &since_date, "TestEprintBlog", None)
+    let window = DateWindow::since(NaiveDate::from_ymd_opt(2024, 9, 1).unwrap());
+    let fetcher = fetcher();
+    let articles = fetcher.fetch_articles(&FetchContext::default(), &mockito::server_url(), &window, "TestEprintBlog", None)
         .await
         .expect("Failed to fetch Eprint articles");
 
-    assert_eq!(articles.len(), 1); // Only one article should be included
+    assert_eq!(articles.len(), 2); // The new paper and the revised one
     assert_eq!(articles[0].title, "Test Title");
     assert_eq!(articles[0].url, "https://eprint.iacr.org/2024/1431");
     assert_eq!(articles[0].authors, Some("Dan Boneh".to_string()));
+    assert_eq!(articles[0].updated, None);
+    assert_eq!(articles[1].url, "https://eprint.iacr.org/2024/4567");
+    assert_eq!(articles[1].published, Utc.with_ymd_and_hms(2024, 6, 19, 2, 36, 4).unwrap());
+    assert_eq!(articles[1].updated, Some(Utc.with_ymd_and_hms(2024, 9, 19, 2, 36, 4).unwrap()));
+}
+
+#[tokio::test]
+async fn test_fetch_eprint_articles_without_revisions() {
+    let _m = mock("GET", "/no-revisions?from=2024-09-01")
+        .with_status(200)
+        .with_header("content-type", "application/xml")
+        .with_body(FILTERING_RESPONSE)
+        .create();
+
+    let config = EprintConfig {
+        keywords: vec!["zero-knowledge".to_string(), "zk".to_string()],
+        include_revisions: false,
+        ..EprintConfig::default()
+    };
+    let fetcher = EprintFetcher::new(&config).unwrap();
+    let window = DateWindow::since(NaiveDate::from_ymd_opt(2024, 9, 1).unwrap());
+    let feed_url = format!("{}/no-revisions", mockito::server_url());
+    let articles = fetcher.fetch_articles(&FetchContext::default(), &feed_url, &window, "TestEprintBlog", None)
+        .await
+        .expect("Failed to fetch Eprint articles");
+
+    let urls: Vec<_> = articles.iter().map(|a| a.url.as_str()).collect();
+    assert_eq!(urls, vec!["https://eprint.iacr.org/2024/1431"]);
+}
+
+#[tokio::test]
+async fn test_fetch_eprint_articles_until() {
+    let _june = mock("GET", "/until?from=2024-06-01")
+        .with_status(200)
+        .with_header("content-type", "application/xml")
+        .with_body(FILTERING_RESPONSE)
+        .create();
+    let feed_url = format!("{}/until", mockito::server_url());
+    let date = |month, day| NaiveDate::from_ymd_opt(2024, month, day).unwrap();
+
+    // The revision posted after the window is not known yet
+    let window = DateWindow::new(date(6, 1), Some(date(6, 30))).unwrap();
+    let articles = fetcher().fetch_articles(&FetchContext::default(), &feed_url, &window, "TestEprintBlog", None)
+        .await
+        .expect("Failed to fetch Eprint articles");
+    let urls: Vec<_> = articles.iter().map(|a| a.url.as_str()).collect();
+    assert_eq!(urls, vec!["https://eprint.iacr.org/2024/4567"]);
+    assert_eq!(articles[0].updated, None);
+
+    let _september = mock("GET", "/until?from=2024-09-01")
+        .with_status(200)
+        .with_header("content-type", "application/xml")
+        .with_body(FILTERING_RESPONSE)
+        .create();
+    let window = DateWindow::new(date(9, 1), Some(date(9, 18))).unwrap();
+    let articles = fetcher().fetch_articles(&FetchContext::default(), &feed_url, &window, "TestEprintBlog", None)
+        .await
+        .expect("Failed to fetch Eprint articles");
+    let urls: Vec<_> = articles.iter().map(|a| a.url.as_str()).collect();
+    assert_eq!(urls, vec!["https://eprint.iacr.org/2024/1431"]);
 }
 
 #[tokio::test]
@@ -229,15 +305,15 @@ async fn test_fetch_eprint_articles_with_exclusion() {
     </OAI-PMH>
     "#;
 
-    let _m = mock("GET", "/")
+    let _m = mock("GET", "/?from=2024-09-01")
         .with_status(200)
         .with_header("content-type", "application/xml")
         .with_body(mock_response)
         .create();
 
-    let since_date = NaiveDate::from_ymd_opt(2024, 9, 1).unwrap();
-    let fetcher = EprintFetcher;
-    let articles = fetcher.fetch_articles(&mockito::server_url(), &since_date, "TestEprintBlog", None)
+    let window = DateWindow::since(NaiveDate::from_ymd_opt(2024, 9, 1).unwrap());
+    let fetcher = fetcher();
+    let articles = fetcher.fetch_articles(&FetchContext::default(), &mockito::server_url(), &window, "TestEprintBlog", None)
         .await
         .expect("Failed to fetch Eprint articles");
 
@@ -245,3 +321,204 @@ async fn test_fetch_eprint_articles_with_exclusion() {
     assert_eq!(articles.len(), 1); // Only two articles should be included
     assert_eq!(articles[0].title, "Test Title");
 }
+
+
+fn list_records_page(identifiers: &[&str], resumption_token: &str) -> String {
+    let records: String = identifiers.iter().map(|id| format!(r#"
+        <record>
+          <header>
+            <identifier>oai:eprint.iacr.org:{id}</identifier>
+            <datestamp>2024-09-18T07:46:25Z</datestamp>
+          </header>
+          <metadata>
+            <oai_dc:dc>
+              <dc:identifier>https://eprint.iacr.org/{id}</dc:identifier>
+              <dc:title>Paper {id}</dc:title>
+              <dc:creator>Some Author</dc:creator>
+              <dc:date>2024-09-18T07:46:25Z</dc:date>
+              <dc:description>A zero-knowledge paper.</dc:description>
+            </oai_dc:dc>
+          </metadata>
+        </record>"#, id = id)).collect();
+    format!(r#"<?xml version="1.0" encoding="UTF-8"?>
+    <OAI-PMH xmlns="http://www.openarchives.org/OAI/2.0/">
+      <ListRecords>{}
+        <resumptionToken completeListSize="3" cursor="0">{}</resumptionToken>
+      </ListRecords>
+    </OAI-PMH>"#, records, resumption_token)
+}
+
+#[tokio::test]
+async fn test_fetch_eprint_articles_follows_resumption_token() {
+    let first_page = mock("GET", "/oai?verb=ListRecords&metadataPrefix=oai_dc&from=2024-09-01")
+        .with_status(200)
+        .with_header("content-type", "application/xml")
+        .with_body(list_records_page(&["2024/1001", "2024/1002"], "token/2"))
+        .expect(1)
+        .create();
+    let second_page = mock("GET", "/oai?verb=ListRecords&resumptionToken=token%2F2")
+        .with_status(200)
+        .with_header("content-type", "application/xml")
+        .with_body(list_records_page(&["2024/1003"], ""))
+        .expect(1)
+        .create();
+
+    let window = DateWindow::since(NaiveDate::from_ymd_opt(2024, 9, 1).unwrap());
+    let feed_url = format!("{}/oai?verb=ListRecords&metadataPrefix=oai_dc", mockito::server_url());
+    let articles = fetcher().fetch_articles(&FetchContext::default(), &feed_url, &window, "TestEprintBlog", None)
+        .await
+        .expect("Failed to fetch Eprint articles");
+
+    first_page.assert();
+    second_page.assert();
+    let urls: Vec<&str> = articles.iter().map(|a| a.url.as_str()).collect();
+    assert_eq!(urls, vec!["https://eprint.iacr.org/2024/1001", "https://eprint.iacr.org/2024/1002", "https://eprint.iacr.org/2024/1003"]);
+}
+
+#[tokio::test]
+async fn test_fetch_eprint_articles_warns_at_page_cap() {
+    // Every page hands out a new token, as a misbehaving server would
+    let served = Arc::new(AtomicUsize::new(0));
+    let counter = served.clone();
+    let pages = mock("GET", Matcher::Regex(r"^/capped\?".to_string()))
+        .with_status(200)
+        .with_body_from_fn(move |w| {
+            let page = counter.fetch_add(1, Ordering::SeqCst);
+            w.write_all(list_records_page(&[], &format!("token-{}", page)).as_bytes())
+        })
+        .expect(3)
+        .create();
+
+    let window = DateWindow::since(NaiveDate::from_ymd_opt(2024, 9, 1).unwrap());
+    let ctx = FetchContext::default();
+    let feed_url = format!("{}/capped?verb=ListRecords&metadataPrefix=oai_dc", mockito::server_url());
+    fetcher().with_max_pages(3).fetch_articles(&ctx, &feed_url, &window, "TestEprintBlog", None)
+        .await
+        .expect("Failed to fetch Eprint articles");
+
+    pages.assert();
+    assert_eq!(ctx.log.warnings(), vec!["Stopped after 3 pages, later records were not fetched"]);
+}
+
+#[tokio::test]
+async fn test_fetch_eprint_articles_oai_errors() {
+    let _empty = mock("GET", "/empty?from=2024-09-01")
+        .with_status(200)
+        .with_body(r#"<OAI-PMH><error code="noRecordsMatch">No records match</error></OAI-PMH>"#)
+        .create();
+    let _bad = mock("GET", "/bad?from=2024-09-01")
+        .with_status(200)
+        .with_body(r#"<OAI-PMH><error code="badArgument">Illegal date</error></OAI-PMH>"#)
+        .create();
+
+    let window = DateWindow::since(NaiveDate::from_ymd_opt(2024, 9, 1).unwrap());
+    let ctx = FetchContext::default();
+
+    let articles = fetcher().fetch_articles(&ctx, &format!("{}/empty", mockito::server_url()), &window, "TestEprintBlog", None)
+        .await
+        .expect("noRecordsMatch should not be an error");
+    assert!(articles.is_empty());
+
+    let result = fetcher().fetch_articles(&ctx, &format!("{}/bad", mockito::server_url()), &window, "TestEprintBlog", None).await;
+    assert_eq!(result.unwrap_err().to_string(), "Parse error: OAI-PMH error badArgument: Illegal date");
+}
+
+#[tokio::test]
+async fn test_fetch_eprint_articles_skips_malformed_records() {
+    let _m = mock("GET", "/malformed?from=2024-09-01")
+        .with_status(200)
+        .with_body(r#"<?xml version="1.0" encoding="UTF-8"?>
+    <OAI-PMH xmlns="http://www.openarchives.org/OAI/2.0/">
+      <ListRecords>
+        <record><metadata><oai_dc:dc>
+          <dc:identifier>https://eprint.iacr.org/2024/1</dc:identifier>
+          <dc:title>Broken &bogus; entity</dc:title>
+          <dc:date>2024-09-18T07:46:25Z</dc:date>
+          <dc:description>A zero-knowledge paper.</dc:description>
+        </oai_dc:dc></metadata></record>
+        <record><metadata><oai_dc:dc>
+          <dc:title>No identifier, no date</dc:title>
+          <dc:description>A zero-knowledge paper.</dc:description>
+        </oai_dc:dc></metadata></record>
+        <record><metadata><oai_dc:dc>
+          <dc:identifier>https://eprint.iacr.org/2024/3</dc:identifier>
+          <dc:title>Readable zero-knowledge paper</dc:title>
+          <dc:date>2024-09-18T07:46:25Z</dc:date>
+        </oai_dc:dc></metadata></record>
+      </ListRecords>
+    </OAI-PMH>"#)
+        .create();
+
+    let window = DateWindow::since(NaiveDate::from_ymd_opt(2024, 9, 1).unwrap());
+    let ctx = FetchContext::default();
+    let feed_url = format!("{}/malformed", mockito::server_url());
+    let articles = fetcher().fetch_articles(&ctx, &feed_url, &window, "TestEprintBlog", None)
+        .await
+        .expect("Malformed records should not fail the source");
+
+    assert_eq!(articles.len(), 1);
+    assert_eq!(articles[0].url, "https://eprint.iacr.org/2024/3");
+    let skipped = ctx.log.skipped();
+    assert_eq!(skipped.len(), 2);
+    assert_eq!(skipped[0].item, "https://eprint.iacr.org/2024/1");
+    assert!(skipped[0].reason.starts_with("XML parse error"));
+    assert_eq!(skipped[1].item, format!("record 2 of {}?from=2024-09-01", feed_url));
+    assert_eq!(skipped[1].reason, "Parse error: Record has no dc:date");
+}
+
+#[tokio::test]
+async fn test_fetch_eprint_articles_malformed_document() {
+    let _m = mock("GET", "/truncated?from=2024-09-01")
+        .with_status(200)
+        .with_body("<OAI-PMH><ListRecords><record></metadata></record></ListRecords></OAI-PMH>")
+        .create();
+
+    let window = DateWindow::since(NaiveDate::from_ymd_opt(2024, 9, 1).unwrap());
+    let result = fetcher().fetch_articles(&FetchContext::default(), &format!("{}/truncated", mockito::server_url()), &window, "TestEprintBlog", None).await;
+    assert!(matches!(result, Err(AppError::ParseError(e)) if e.starts_with("Malformed ListRecords response at byte")));
+}
+
+#[test]
+fn test_eprint_fetcher_loads_config_from_blog_info() {
+    let mut config_file = tempfile::NamedTempFile::new().expect("Failed to create temporary file");
+    std::io::Write::write_all(&mut config_file, br#"{"keywords": ["homomorphic"]}"#).expect("Failed to write config");
+
+    let mut blog: BlogInfo = serde_json::from_value(serde_json::json!({
+        "name": "ePrint FHE",
+        "domain": "https://eprint.iacr.org/oai",
+        "feed_type": "Eprint",
+        "eprint_config": config_file.path().to_str().unwrap()
+    })).expect("Failed to build blog");
+    assert!(EprintFetcher::from_blog_info(&blog).is_ok());
+
+    blog.eprint_config = Some("./config/missing.json".to_string());
+    let error = EprintFetcher::from_blog_info(&blog).err().expect("A missing config should be an error");
+    assert!(error.to_string().contains("Failed to open ePrint config ./config/missing.json"));
+}
+
+#[tokio::test]
+async fn test_fetch_eprint_articles_leaves_top_n_to_the_aggregator() {
+    let _m = mock("GET", "/ranked?from=2024-09-01")
+        .with_status(200)
+        .with_body(list_records_page(&["2024/2001", "2024/2002", "2024/2003"], ""))
+        .create();
+
+    let mut config = EprintConfig {
+        keywords: vec!["zero-knowledge".to_string(), "paper".to_string()],
+        authors: vec!["Some Author".to_string()],
+        ..EprintConfig::default()
+    };
+    config.scoring.top_n = Some(2);
+    let fetcher = EprintFetcher::new(&config).expect("Failed to build fetcher");
+
+    let window = DateWindow::since(NaiveDate::from_ymd_opt(2024, 9, 1).unwrap());
+    let articles = fetcher.fetch_articles(&FetchContext::default(), &format!("{}/ranked", mockito::server_url()), &window, "TestEprintBlog", None)
+        .await
+        .expect("Failed to fetch Eprint articles");
+
+    // Every paper scores 3 + 1 for "paper", 1 for "zero-knowledge" and 5 for the author
+    let urls: Vec<&str> = articles.iter().map(|a| a.url.as_str()).collect();
+    assert_eq!(urls, vec!["https://eprint.iacr.org/2024/2001", "https://eprint.iacr.org/2024/2002", "https://eprint.iacr.org/2024/2003"]);
+    assert!(articles.iter().all(|a| a.score == Some(10.0)));
+    assert_eq!(fetcher.top_n(), Some(2));
+}
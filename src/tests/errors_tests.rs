@@ -10,7 +10,7 @@ use std::io::Write;
 #[test]
 fn test_app_error_display() {
 
-    //TODO: AppError::NetworkError, AppError::AsyncRuntimeError, AppError::XmlError
+    //TODO: AppError::NetworkError, AppError::AsyncRuntimeError
 
     let parse_error = AppError::ParseError("Invalid format".to_string());
     assert_eq!(parse_error.to_string(), "Parse error: Invalid format");
@@ -24,6 +24,9 @@ fn test_app_error_display() {
 
     let unknown_feed_type = AppError::UnknownFeedType("InvalidFeed".to_string());
     assert_eq!(unknown_feed_type.to_string(), "Unknown feed type: InvalidFeed");
+
+    let http_error = AppError::HttpError("Response too large".to_string());
+    assert_eq!(http_error.to_string(), "HTTP error: Response too large");
 }
 
 #[test]
@@ -54,13 +57,6 @@ fn test_app_error_from_atom_syndication_error() {
     assert!(matches!(app_error, AppError::ParseError(_)));
 }
 
-#[test]
-fn test_app_error_from_rss_error() {
-    let rss_error = rss::Error::InvalidStartTag;
-    let app_error: AppError = AppError::ParseError(rss_error.to_string());
-    assert!(matches!(app_error, AppError::ParseError(_)));
-}
-
 #[test]
 fn test_app_error_from_serde_json() {
     let json_error = serde_json::from_str::<serde_json::Value>("invalid json").unwrap_err();
@@ -75,13 +71,6 @@ fn test_app_error_from_atom_syndication() {
     assert!(matches!(app_error, AppError::ParseError(_)));
 }
 
-#[test]
-fn test_app_error_from_rss() {
-    let rss_error = rss::Error::InvalidStartTag;
-    let app_error: AppError = rss_error.into();
-    assert!(matches!(app_error, AppError::ParseError(_)));
-}
-
 #[test]
 fn test_read_blogs_from_file_invalid_format() {
     let temp_file = NamedTempFile::new().expect("Failed to create temporary file");
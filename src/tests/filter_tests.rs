@@ -0,0 +1,218 @@
+use crate::errors::AppError;
+use crate::filter::{ExcludeRule, KeywordRule, Paper, PaperFilter, ScoringConfig};
+use crate::models::EprintConfig;
+use std::collections::HashMap;
+
+fn paper<'a>(title: &'a str, description: &'a str, authors: &'a [String]) -> Paper<'a> {
+    Paper {
+        identifier: "https://eprint.iacr.org/2024/1",
+        title,
+        description,
+        subject: "",
+        authors,
+    }
+}
+
+fn config(keywords: &[&str], authors: &[&str]) -> EprintConfig {
+    EprintConfig {
+        keywords: keywords.iter().map(|k| k.to_string()).collect(),
+        authors: authors.iter().map(|a| a.to_string()).collect(),
+        aliases: HashMap::new(),
+        exclude: vec![ExcludeRule::from("2024/9999".to_string())],
+        scoring: ScoringConfig::default(),
+        include_revisions: false,
+        categories: Vec::new(),
+    }
+}
+
+#[test]
+fn test_keyword_rule_whole_word() {
+    let rule = KeywordRule::parse("stark").unwrap();
+    assert!(rule.is_match("A STARK for everyone"));
+    assert!(!rule.is_match("A starkly different approach"));
+
+    let rule = KeywordRule::parse("zk").unwrap();
+    assert!(rule.is_match("Lookups for zk-SNARKs"));
+    assert!(!rule.is_match("Fuzzing the zkEVM"));
+}
+
+#[test]
+fn test_keyword_rule_prefix_and_phrase() {
+    let rule = KeywordRule::parse("snark*").unwrap();
+    assert!(rule.is_match("Recursive SNARKs"));
+    assert!(!rule.is_match("zkSNARK"));
+
+    let rule = KeywordRule::parse("zero knowledge").unwrap();
+    assert!(rule.is_match("Zero\n  Knowledge proofs"));
+    assert!(!rule.is_match("zero-knowledge proofs"));
+    assert!(!rule.is_match("zero knowledgeable"));
+}
+
+#[test]
+fn test_keyword_rule_regex_and_negative() {
+    let rule = KeywordRule::parse("/zk-?snarks?/").unwrap();
+    assert!(rule.is_match("Universal zkSNARKs"));
+    assert!(rule.is_match("a ZK-SNARK"));
+    assert!(!rule.negative);
+
+    let rule = KeywordRule::parse("-lattice").unwrap();
+    assert!(rule.negative);
+    assert_eq!(rule.keyword, "lattice");
+    assert!(rule.is_match("Lattice-based commitments"));
+
+    assert!(KeywordRule::parse("/unclosed(/").is_err());
+}
+
+#[test]
+fn test_keyword_rule_rejects_empty_keywords() {
+    for keyword in ["", "  ", "*", "-", "-*", "//"] {
+        let err = KeywordRule::parse(keyword).err().unwrap_or_else(|| panic!("{:?} was accepted", keyword));
+        assert!(matches!(err, AppError::ParseError(_)), "{:?}: {}", keyword, err);
+    }
+    assert!(KeywordRule::parse("-zk*").is_ok());
+}
+
+fn rules(filter: &PaperFilter, paper: &Paper) -> Option<String> {
+    filter.score(paper).map(|relevance| relevance.rules.join(", "))
+}
+
+#[test]
+fn test_paper_filter_reports_matched_rules() {
+    let filter = PaperFilter::new(&config(&["stark", "zero knowledge", "-lattice"], &["Dan Boneh"])).unwrap();
+    let no_authors: Vec<String> = Vec::new();
+    let boneh = vec!["Dan Boneh".to_string()];
+
+    assert_eq!(rules(&filter, &paper("Faster STARK provers", "", &no_authors)), Some("keyword \"stark\" in title".to_string()));
+    assert_eq!(rules(&filter, &paper("Provers", "Using zero knowledge", &no_authors)), Some("keyword \"zero knowledge\" in description".to_string()));
+    assert_eq!(rules(&filter, &paper("Starkly different", "", &no_authors)), None);
+    assert_eq!(rules(&filter, &paper("Signatures", "", &boneh)), Some("author Dan Boneh".to_string()));
+
+    // With the default weights, negative keywords outweigh a keyword or a followed author
+    assert_eq!(rules(&filter, &paper("Lattice-based STARK", "", &no_authors)), None);
+    assert_eq!(rules(&filter, &paper("Lattice signatures", "", &boneh)), None);
+
+    let excluded = Paper { identifier: "https://eprint.iacr.org/2024/9999", ..paper("Faster STARK provers", "", &no_authors) };
+    assert_eq!(filter.score(&excluded), None);
+}
+
+#[test]
+fn test_paper_filter_scores() {
+    let mut config = config(&["stark", "zero knowledge", "-lattice"], &["Dan Boneh"]);
+    config.scoring.exclude_penalty = 4.0;
+    let filter = PaperFilter::new(&config).unwrap();
+    let boneh = vec!["Dan Boneh".to_string()];
+
+    // 3 ("stark" in title) + 1 ("stark" in description) + 1 ("zero knowledge" in description) + 5 (author)
+    let relevance = filter.score(&paper("STARK provers", "A STARK with zero knowledge", &boneh)).unwrap();
+    assert_eq!(relevance.score, 3.0 + 1.0 + 1.0 + 5.0);
+    assert_eq!(relevance.rules, vec![
+        "keyword \"stark\" in title",
+        "keyword \"stark\" in description",
+        "keyword \"zero knowledge\" in description",
+        "author Dan Boneh",
+    ]);
+
+    let relevance = filter.score(&paper("Lattice-based STARK", "", &boneh)).unwrap();
+    assert_eq!(relevance.score, 3.0 + 5.0 - 4.0);
+    assert_eq!(relevance.rules.last().unwrap(), "negative keyword \"lattice\"");
+
+    config.scoring.threshold = 5.0;
+    let filter = PaperFilter::new(&config).unwrap();
+    assert!(filter.score(&paper("STARK provers", "", &[])).is_none());
+    assert!(filter.score(&paper("STARK provers", "A STARK with zero knowledge", &[])).is_some());
+}
+
+#[test]
+fn test_paper_filter_matches_author_variants() {
+    let mut config = config(&[], &["Dan Boneh", "Benedikt Bünz"]);
+    config.aliases.insert("Benedikt Bünz".to_string(), vec!["Benedikt Buenz".to_string()]);
+    let filter = PaperFilter::new(&config).unwrap();
+
+    for author in ["D. Boneh", "Boneh, Dan", "DAN BONEH", "Benedikt Bunz", "Bünz, B.", "Benedikt Buenz"] {
+        let authors = vec![author.to_string()];
+        assert!(filter.score(&paper("Untitled", "", &authors)).is_some(), "{} should match", author);
+    }
+    for author in ["Daniel Bonehill", "Eve Boneh", "Buenz"] {
+        let authors = vec![author.to_string()];
+        assert!(filter.score(&paper("Untitled", "", &authors)).is_none(), "{} should not match", author);
+    }
+
+    // The rule names the followed author, not the spelling on the paper
+    let authors = vec!["Bünz, Benedikt".to_string()];
+    assert_eq!(rules(&filter, &paper("Untitled", "", &authors)), Some("author Benedikt Bünz".to_string()));
+}
+
+#[test]
+fn test_exclude_rules_deserialize() {
+    let config: EprintConfig = serde_json::from_str(r#"{"exclude": [
+        "2024/1613",
+        {"author": "John Doe", "until": "2025-06-30"},
+        {"subject": "quantum", "title": "^a survey"}
+    ]}"#).unwrap();
+
+    assert_eq!(config.exclude, vec![
+        ExcludeRule::from("2024/1613".to_string()),
+        ExcludeRule {
+            author: Some("John Doe".to_string()),
+            until: chrono::NaiveDate::from_ymd_opt(2025, 6, 30),
+            ..ExcludeRule::default()
+        },
+        ExcludeRule {
+            subject: Some("quantum".to_string()),
+            title: Some("^a survey".to_string()),
+            ..ExcludeRule::default()
+        },
+    ]);
+}
+
+#[test]
+fn test_paper_filter_exclude_rules() {
+    let mut config = config(&["stark"], &[]);
+    config.exclude.push(ExcludeRule { author: Some("J. Doe".to_string()), ..ExcludeRule::default() });
+    config.exclude.push(ExcludeRule { title: Some("^(a )?survey".to_string()), ..ExcludeRule::default() });
+    config.exclude.push(ExcludeRule {
+        subject: Some("quantum".to_string()),
+        title: Some("post-quantum".to_string()),
+        ..ExcludeRule::default()
+    });
+    let filter = PaperFilter::new(&config).unwrap();
+    let doe = vec!["Alice Smith".to_string(), "Doe, John".to_string()];
+    let smith = vec!["Alice Smith".to_string()];
+
+    assert!(filter.score(&paper("STARK provers", "", &doe)).is_none());
+    assert!(filter.score(&paper("STARK provers", "", &smith)).is_some());
+    assert!(filter.score(&paper("A Survey of STARK provers", "", &smith)).is_none());
+    assert!(filter.score(&paper("STARK provers, a survey", "", &smith)).is_some());
+
+    // Every field of a rule must match
+    let quantum = Paper { subject: "Quantum cryptography", ..paper("Post-quantum STARK provers", "", &smith) };
+    assert!(filter.score(&quantum).is_none());
+    let classical = Paper { subject: "Quantum cryptography", ..paper("STARK provers", "", &smith) };
+    assert!(filter.score(&classical).is_some());
+}
+
+#[test]
+fn test_paper_filter_ignores_expired_exclude_rules() {
+    let mut config = config(&["stark"], &[]);
+    config.exclude.push(ExcludeRule {
+        author: Some("John Doe".to_string()),
+        until: chrono::NaiveDate::from_ymd_opt(2025, 6, 30),
+        ..ExcludeRule::default()
+    });
+    let doe = vec!["John Doe".to_string()];
+
+    let filter = PaperFilter::new_on(&config, chrono::NaiveDate::from_ymd_opt(2025, 6, 30).unwrap()).unwrap();
+    assert!(filter.score(&paper("STARK provers", "", &doe)).is_none());
+    let filter = PaperFilter::new_on(&config, chrono::NaiveDate::from_ymd_opt(2025, 7, 1).unwrap()).unwrap();
+    assert!(filter.score(&paper("STARK provers", "", &doe)).is_some());
+}
+
+#[test]
+fn test_paper_filter_rejects_invalid_exclude_rules() {
+    let mut config = config(&[], &[]);
+    config.exclude.push(ExcludeRule { until: chrono::NaiveDate::from_ymd_opt(2099, 1, 1), ..ExcludeRule::default() });
+    assert!(PaperFilter::new(&config).is_err());
+
+    config.exclude = vec![ExcludeRule { title: Some("(unclosed".to_string()), ..ExcludeRule::default() }];
+    assert!(PaperFilter::new(&config).is_err());
+}
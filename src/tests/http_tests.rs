@@ -0,0 +1,63 @@
+use crate::errors::AppError;
+use crate::http::{HttpClient, HttpConfig};
+use mockito::mock;
+use std::collections::HashMap;
+
+#[tokio::test]
+async fn test_http_client_sends_user_agent_and_default_headers() {
+    let _m = mock("GET", "/headers")
+        .match_header("user-agent", "zkmesh-test/1.0")
+        .match_header("x-api-key", "secret")
+        .with_status(200)
+        .with_body("ok")
+        .create();
+
+    let config = HttpConfig {
+        user_agent: "zkmesh-test/1.0".to_string(),
+        default_headers: HashMap::from([("X-Api-Key".to_string(), "secret".to_string())]),
+        ..HttpConfig::default()
+    };
+    let client = HttpClient::new(&config).expect("Failed to build HTTP client");
+
+    let body = client.get_text(&format!("{}/headers", mockito::server_url()))
+        .await
+        .expect("Failed to fetch");
+    assert_eq!(body, "ok");
+}
+
+#[tokio::test]
+async fn test_http_client_rejects_large_body() {
+    let _m = mock("GET", "/large")
+        .with_status(200)
+        .with_body("x".repeat(1024))
+        .create();
+
+    let config = HttpConfig {
+        max_body_bytes: 100,
+        ..HttpConfig::default()
+    };
+    let client = HttpClient::new(&config).expect("Failed to build HTTP client");
+
+    let result = client.get_bytes(&format!("{}/large", mockito::server_url())).await;
+    assert!(matches!(result, Err(AppError::HttpError(e)) if e.contains("exceeds 100 bytes")));
+}
+
+#[tokio::test]
+async fn test_http_client_error_status() {
+    let _m = mock("GET", "/missing")
+        .with_status(404)
+        .create();
+
+    let client = HttpClient::default();
+    let result = client.get_bytes(&format!("{}/missing", mockito::server_url())).await;
+    assert!(matches!(result, Err(AppError::HttpError(e)) if e.ends_with("returned 404 Not Found")));
+}
+
+#[test]
+fn test_http_client_invalid_config() {
+    let config = HttpConfig {
+        default_headers: HashMap::from([("Bad Header".to_string(), "value".to_string())]),
+        ..HttpConfig::default()
+    };
+    assert!(matches!(HttpClient::new(&config), Err(AppError::HttpError(_))));
+}
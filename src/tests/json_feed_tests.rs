@@ -0,0 +1,159 @@
+use crate::feed_types::{ArticleFetcher, FetchContext, JsonFeedFetcher};
+use crate::dates::DateWindow;
+use chrono::{NaiveDate, TimeZone, Utc};
+use mockito::mock;
+
+#[tokio::test]
+async fn test_fetch_json_feed_articles() {
+    let mock_response = r#"{
+        "version": "https://jsonfeed.org/version/1.1",
+        "title": "Test JSON Feed",
+        "authors": [{"name": "Feed Author"}],
+        "items": [
+            {
+                "id": "1",
+                "url": "https://test.com/posts/new",
+                "title": "New Post",
+                "summary": "What is new",
+                "date_published": "2024-10-01T12:00:00+02:00",
+                "date_modified": "2024-10-03T08:30:00.123Z",
+                "authors": [{"name": "Alice"}, {"name": "Bob"}],
+                "tags": ["zk", "proofs"]
+            },
+            {
+                "id": "2",
+                "url": "https://test.com/posts/microblog",
+                "summary": "A post without a title",
+                "date_published": "2024-09-15T12:00:00Z"
+            },
+            {
+                "id": "3",
+                "url": "https://test.com/posts/old",
+                "title": "Old Post",
+                "date_published": "2024-08-01T12:00:00Z"
+            }
+        ]
+    }"#;
+
+    let _m = mock("GET", "/feed.json")
+        .with_status(200)
+        .with_header("content-type", "application/feed+json")
+        .with_body(mock_response)
+        .create();
+
+    let window = DateWindow::since(NaiveDate::from_ymd_opt(2024, 9, 1).unwrap());
+    let feed_url = format!("{}/feed.json", mockito::server_url());
+    let articles = JsonFeedFetcher.fetch_articles(&FetchContext::default(), &feed_url, &window, "TestJsonFeedBlog", None)
+        .await
+        .expect("Failed to fetch JSON Feed articles");
+
+    assert_eq!(articles.len(), 2);
+    assert_eq!(articles[0].title, "New Post");
+    assert_eq!(articles[0].url, "https://test.com/posts/new");
+    assert_eq!(articles[0].published, Utc.with_ymd_and_hms(2024, 10, 1, 10, 0, 0).unwrap());
+    assert_eq!(articles[0].updated, Some("2024-10-03T08:30:00.123Z".parse().unwrap()));
+    assert_eq!(articles[0].authors, Some("Alice and Bob".to_string()));
+    assert_eq!(articles[0].summary, Some("What is new".to_string()));
+    assert_eq!(articles[0].tags, vec!["zk", "proofs"]);
+    assert_eq!(articles[0].blog_name, "TestJsonFeedBlog");
+    assert_eq!(articles[1].title, "A post without a title");
+    assert_eq!(articles[1].authors, Some("Feed Author".to_string()));
+    assert_eq!(articles[1].updated, None);
+    assert!(articles[1].tags.is_empty());
+}
+
+#[tokio::test]
+async fn test_fetch_json_feed_articles_with_url_replace() {
+    let mock_response = r#"{
+        "version": "https://jsonfeed.org/version/1",
+        "title": "Test JSON Feed 1.0",
+        "author": {"name": "Old Style Author"},
+        "items": [
+            {
+                "id": "https://old.test.com/post",
+                "external_url": "https://old.test.com/post",
+                "title": "Post",
+                "date_modified": "2024-09-10T00:00:00Z"
+            }
+        ]
+    }"#;
+
+    let _m = mock("GET", "/feed-v1.json")
+        .with_status(200)
+        .with_body(mock_response)
+        .create();
+
+    let window = DateWindow::since(NaiveDate::from_ymd_opt(2024, 9, 1).unwrap());
+    let feed_url = format!("{}/feed-v1.json", mockito::server_url());
+    let articles = JsonFeedFetcher.fetch_articles(&FetchContext::default(), &feed_url, &window, "TestJsonFeedBlog", Some("old.test.com > test.com".to_string()))
+        .await
+        .expect("Failed to fetch JSON Feed articles");
+
+    assert_eq!(articles.len(), 1);
+    assert_eq!(articles[0].url, "https://test.com/post");
+    assert_eq!(articles[0].published, Utc.with_ymd_and_hms(2024, 9, 10, 0, 0, 0).unwrap());
+    assert_eq!(articles[0].authors, Some("Old Style Author".to_string()));
+}
+
+#[tokio::test]
+async fn test_fetch_json_feed_articles_missing_date() {
+    let _m = mock("GET", "/feed-undated.json")
+        .with_status(200)
+        .with_body(r#"{"version": "https://jsonfeed.org/version/1.1", "items": [{"id": "1", "url": "https://test.com/a", "title": "A"}]}"#)
+        .create();
+
+    let window = DateWindow::since(NaiveDate::from_ymd_opt(2024, 9, 1).unwrap());
+    let feed_url = format!("{}/feed-undated.json", mockito::server_url());
+    let result = JsonFeedFetcher.fetch_articles(&FetchContext::default(), &feed_url, &window, "TestJsonFeedBlog", None).await;
+
+    assert_eq!(result.unwrap_err().to_string(), "Parse error: None of the 1 items could be read, the first failed with: Parse error: Missing publication date");
+}
+
+#[tokio::test]
+async fn test_fetch_json_feed_articles_skips_bad_items() {
+    let _m = mock("GET", "/feed-partly-broken.json")
+        .with_status(200)
+        .with_body(r#"{"version": "https://jsonfeed.org/version/1.1", "items": [
+            {"id": "1", "url": "https://test.com/a", "title": "A", "date_published": "2024-09-10T10:00:00Z"},
+            {"id": "2", "url": "https://test.com/b", "title": "B", "date_published": "last week"},
+            {"id": "3", "url": "https://test.com/c", "title": ["not", "a", "title"]}
+        ]}"#)
+        .create();
+
+    let ctx = FetchContext::default();
+    let window = DateWindow::since(NaiveDate::from_ymd_opt(2024, 9, 1).unwrap());
+    let feed_url = format!("{}/feed-partly-broken.json", mockito::server_url());
+    let articles = JsonFeedFetcher.fetch_articles(&ctx, &feed_url, &window, "TestJsonFeedBlog", None)
+        .await
+        .expect("Failed to fetch JSON Feed articles");
+
+    assert_eq!(articles.len(), 1);
+    assert_eq!(articles[0].url, "https://test.com/a");
+    let skipped = ctx.log.skipped();
+    assert_eq!(skipped.len(), 2);
+    assert_eq!(skipped[0].item, "https://test.com/b");
+    assert_eq!(skipped[1].item, "https://test.com/c");
+    assert!(skipped.iter().all(|item| !item.reason.is_empty()));
+}
+
+#[tokio::test]
+async fn test_fetch_json_feed_articles_ignores_bad_modified_date() {
+    let _m = mock("GET", "/feed-bad-modified.json")
+        .with_status(200)
+        .with_body(r#"{"version": "https://jsonfeed.org/version/1.1", "items": [
+            {"id": "1", "url": "https://test.com/a", "title": "A", "date_published": "2024-09-10T10:00:00Z", "date_modified": "sometime later"}
+        ]}"#)
+        .create();
+
+    let ctx = FetchContext::default();
+    let window = DateWindow::since(NaiveDate::from_ymd_opt(2024, 9, 1).unwrap());
+    let feed_url = format!("{}/feed-bad-modified.json", mockito::server_url());
+    let articles = JsonFeedFetcher.fetch_articles(&ctx, &feed_url, &window, "TestJsonFeedBlog", None)
+        .await
+        .expect("Failed to fetch JSON Feed articles");
+
+    assert_eq!(articles.len(), 1);
+    assert_eq!(articles[0].published, Utc.with_ymd_and_hms(2024, 9, 10, 10, 0, 0).unwrap());
+    assert_eq!(articles[0].updated, None);
+    assert!(ctx.log.skipped().is_empty());
+}
@@ -1,5 +1,6 @@
-mod main_tests;
+mod aggregator_tests;
 mod feed_types_tests;
+mod registry_tests;
 mod substack_tests;
 mod rss_tests;
 mod atom_tests;
@@ -7,4 +8,16 @@ mod custom_html_tests;
 mod errors_tests;
 mod utils_tests;
 mod config_tests;
-mod eprint_tests;
\ No newline at end of file
+mod http_tests;
+mod retry_tests;
+mod rate_limit_tests;
+mod cache_tests;
+mod replay_tests;
+mod eprint_tests;
+mod filter_tests;
+mod names_tests;
+mod arxiv_tests;
+mod json_feed_tests;
+mod auto_tests;
+mod syndication_tests;
+mod dates_tests;
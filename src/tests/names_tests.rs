@@ -0,0 +1,53 @@
+use crate::names::PersonName;
+
+fn name(text: &str) -> PersonName {
+    PersonName::parse(text).expect("Failed to parse name")
+}
+
+#[test]
+fn test_parse_person_name() {
+    assert_eq!(name("Ulrich Haböck"), PersonName { family: "habock".to_string(), given: vec!["ulrich".to_string()] });
+    assert_eq!(name("Haböck, Ulrich"), name("Ulrich Haböck"));
+    assert_eq!(name("Zachary J. Williamson").given, vec!["zachary", "j"]);
+    assert_eq!(name("van der Berg, Jan").family, "van der berg");
+    assert_eq!(name("Eli Ben-Sasson").family, "ben-sasson");
+    assert_eq!(name("Tomasz Łukasz Ślęzak").family, "slezak");
+    assert!(PersonName::parse("  ").is_none());
+}
+
+#[test]
+fn test_parse_person_name_particles() {
+    let expected = PersonName { family: "van der berg".to_string(), given: vec!["jan".to_string()] };
+    assert_eq!(name("Jan van der Berg"), expected);
+    assert_eq!(name("van der Berg, Jan"), expected);
+    assert_eq!(name("Berg, Jan van der"), expected);
+    assert_eq!(name("Ludwig van Beethoven").family, "van beethoven");
+    assert_eq!(name("Maria di Stefano").family, "di stefano");
+    // Capitalised, the word is a given name
+    assert_eq!(name("Van Morrison"), PersonName { family: "morrison".to_string(), given: vec!["van".to_string()] });
+}
+
+#[test]
+fn test_parse_person_name_suffixes() {
+    let expected = name("Dan Boneh");
+    assert_eq!(name("Dan Boneh Jr."), expected);
+    assert_eq!(name("Dan Boneh Sr"), expected);
+    assert_eq!(name("Boneh, Dan, Jr."), expected);
+    assert_eq!(name("Boneh Jr., Dan"), expected);
+    assert_eq!(name("Dan Boneh III").family, "boneh");
+    assert_eq!(name("Dan Boneh II").given, vec!["dan"]);
+}
+
+#[test]
+fn test_person_name_matches() {
+    assert!(name("D. Boneh").matches(&name("Dan Boneh")));
+    assert!(name("Boneh, D.").matches(&name("Dan Boneh")));
+    assert!(name("Benjamin E. Diamond").matches(&name("Benjamin Diamond")));
+    assert!(name("Benedikt Bunz").matches(&name("Benedikt Bünz")));
+    assert!(name("Jan van der Berg").matches(&name("van der Berg, J.")));
+    assert!(name("Dan Boneh Jr.").matches(&name("D. Boneh")));
+
+    assert!(!name("Dan Boneh").matches(&name("Daniel Boneh")));
+    assert!(!name("E. Boneh").matches(&name("Dan Boneh")));
+    assert!(!name("Boneh").matches(&name("Dan Boneh")));
+}
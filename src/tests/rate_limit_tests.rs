@@ -0,0 +1,129 @@
+use crate::aggregator::Aggregator;
+use crate::errors::AppError;
+use crate::feed_types::{ArticleFetcher, FetchContext, FetcherRegistry};
+use crate::models::{BlogArticle, BlogInfo};
+use crate::rate_limit::{HostLimit, HostRateLimiter, RateLimitConfig};
+use crate::dates::DateWindow;
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+fn config(requests_per_second: f64, burst: u32) -> RateLimitConfig {
+    RateLimitConfig {
+        requests_per_second,
+        burst,
+        ..RateLimitConfig::default()
+    }
+}
+
+#[tokio::test]
+async fn test_rate_limiter_throttles_same_host() {
+    let limiter = HostRateLimiter::new(&config(20.0, 1));
+
+    let start = Instant::now();
+    for _ in 0..3 {
+        limiter.acquire("https://medium.com/feed/a").await;
+    }
+    // The first request uses the burst, the two others wait 50ms each
+    assert!(start.elapsed() >= Duration::from_millis(95));
+}
+
+#[tokio::test]
+async fn test_rate_limiter_does_not_throttle_different_hosts() {
+    let limiter = HostRateLimiter::new(&config(1.0, 1));
+
+    let start = Instant::now();
+    limiter.acquire("https://medium.com/feed/a").await;
+    limiter.acquire("https://mirror.xyz/feed/atom").await;
+    limiter.acquire("https://blog.example.com/rss").await;
+    assert!(start.elapsed() < Duration::from_millis(500));
+}
+
+#[tokio::test]
+async fn test_rate_limiter_host_override() {
+    let limiter = HostRateLimiter::new(&RateLimitConfig {
+        hosts: HashMap::from([("medium.com".to_string(), HostLimit::Rate(0.0))]),
+        ..config(1.0, 1)
+    });
+
+    let start = Instant::now();
+    for _ in 0..5 {
+        limiter.acquire("https://medium.com/feed/a").await;
+    }
+    assert!(start.elapsed() < Duration::from_millis(500));
+}
+
+#[tokio::test]
+async fn test_rate_limiter_host_burst_override() {
+    let limiter = HostRateLimiter::new(&RateLimitConfig {
+        hosts: HashMap::from([("export.arxiv.org".to_string(), HostLimit::Bucket { requests_per_second: 20.0, burst: Some(1) })]),
+        ..config(20.0, 4)
+    });
+
+    let start = Instant::now();
+    for _ in 0..3 {
+        limiter.acquire("http://export.arxiv.org/api/query").await;
+    }
+    // Without the default burst of 4, the two last requests wait 50ms each
+    assert!(start.elapsed() >= Duration::from_millis(95));
+}
+
+#[test]
+fn test_rate_limit_hosts_deserialize() {
+    let config: RateLimitConfig = serde_json::from_value(serde_json::json!({
+        "hosts": {
+            "medium.com": 0.5,
+            "export.arxiv.org": {"requests_per_second": 0.33, "burst": 1},
+            "mirror.xyz": {"requests_per_second": 1}
+        }
+    })).unwrap();
+
+    assert_eq!(config.hosts["medium.com"], HostLimit::Rate(0.5));
+    assert_eq!(config.hosts["export.arxiv.org"], HostLimit::Bucket { requests_per_second: 0.33, burst: Some(1) });
+    assert_eq!(config.hosts["mirror.xyz"], HostLimit::Bucket { requests_per_second: 1.0, burst: None });
+}
+
+struct SlowFetcher {
+    running: Arc<AtomicUsize>,
+    max_running: Arc<AtomicUsize>,
+}
+
+#[async_trait]
+impl ArticleFetcher for SlowFetcher {
+    async fn fetch_articles(&self, _ctx: &FetchContext, _feed_url: &str, _window: &DateWindow, _blog_name: &str, _custom_url_replace: Option<String>) -> Result<Vec<BlogArticle>, AppError> {
+        let running = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+        self.max_running.fetch_max(running, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        self.running.fetch_sub(1, Ordering::SeqCst);
+        Ok(Vec::new())
+    }
+}
+
+#[tokio::test]
+async fn test_aggregator_caps_concurrent_sources() {
+    let running = Arc::new(AtomicUsize::new(0));
+    let max_running = Arc::new(AtomicUsize::new(0));
+
+    let mut registry = FetcherRegistry::default();
+    let (r, m) = (running.clone(), max_running.clone());
+    registry.register("Slow", move |_| Ok(Box::new(SlowFetcher { running: r.clone(), max_running: m.clone() })));
+
+    let blogs: Vec<BlogInfo> = (0..6).map(|i| serde_json::from_value(serde_json::json!({
+        "name": format!("Slow{}", i),
+        "domain": "https://test.com",
+        "feed_type": "Slow"
+    })).expect("Failed to build BlogInfo")).collect();
+
+    let window = DateWindow::since(NaiveDate::from_ymd_opt(2024, 9, 1).unwrap());
+    let report = Aggregator::new(blogs)
+        .with_registry(registry)
+        .with_rate_limit(&RateLimitConfig { max_concurrent_sources: 2, ..RateLimitConfig::default() })
+        .fetch(&window)
+        .await;
+
+    assert!(report.errors.is_empty());
+    assert_eq!(max_running.load(Ordering::SeqCst), 2);
+}
@@ -0,0 +1,80 @@
+use crate::aggregator::Aggregator;
+use crate::errors::AppError;
+use crate::feed_types::{ArticleFetcher, FeedType, FetchContext, FetcherRegistry};
+use crate::models::{BlogArticle, BlogInfo};
+use crate::dates::DateWindow;
+use async_trait::async_trait;
+use chrono::{NaiveDate, NaiveTime};
+
+struct StaticFetcher {
+    title: String,
+}
+
+#[async_trait]
+impl ArticleFetcher for StaticFetcher {
+    async fn fetch_articles(&self, _ctx: &FetchContext, feed_url: &str, window: &DateWindow, blog_name: &str, _custom_url_replace: Option<String>) -> Result<Vec<BlogArticle>, AppError> {
+        Ok(vec![BlogArticle {
+            title: self.title.clone(),
+            url: feed_url.to_string(),
+            published: window.since.and_time(NaiveTime::MIN).and_utc(),
+            updated: None,
+            blog_name: blog_name.to_string(),
+            authors: None,
+            matched_rule: None,
+            score: None,
+            summary: None,
+            tags: Vec::new(),
+        }])
+    }
+}
+
+fn blog(name: &str, feed_type: &str) -> BlogInfo {
+    serde_json::from_value(serde_json::json!({
+        "name": name,
+        "domain": "https://test.com",
+        "feed_type": feed_type
+    })).expect("Failed to build BlogInfo")
+}
+
+#[test]
+fn test_default_registry_knows_builtin_types() {
+    let registry = FetcherRegistry::default();
+    for feed_type in FeedType::BUILTIN.iter() {
+        assert!(registry.contains(feed_type.name()));
+    }
+    assert!(!registry.contains("Static"));
+}
+
+#[test]
+fn test_feed_type_deserializes_unknown_name_as_custom() {
+    assert_eq!(blog("TestBlog", "RSS").feed_type, FeedType::RSS);
+    assert_eq!(blog("TestBlog", "Static").feed_type, FeedType::Custom("Static".to_string()));
+    assert_eq!(serde_json::to_value(FeedType::Custom("Static".to_string())).unwrap(), "Static");
+}
+
+#[test]
+fn test_registry_build_unknown_feed_type() {
+    let registry = FetcherRegistry::default();
+    let result = registry.build(&blog("TestBlog", "Static"));
+    assert!(matches!(result, Err(AppError::UnknownFeedType(name)) if name == "Static"));
+}
+
+#[test]
+fn test_registry_build_custom_html_without_selectors() {
+    let registry = FetcherRegistry::default();
+    assert!(registry.build(&blog("TestBlog", "CustomHTML")).is_err());
+}
+
+#[tokio::test]
+async fn test_aggregator_with_custom_fetcher() {
+    let mut registry = FetcherRegistry::default();
+    registry.register("Static", |blog| Ok(Box::new(StaticFetcher { title: format!("Hello from {}", blog.name) })));
+
+    let blogs = vec![blog("MyBlog", "Static"), blog("Unregistered", "Other")];
+    let window = DateWindow::since(NaiveDate::from_ymd_opt(2024, 9, 1).unwrap());
+    let report = Aggregator::new(blogs).with_registry(registry).fetch(&window).await;
+
+    assert_eq!(report.other_articles.len(), 1);
+    assert_eq!(report.other_articles[0].title, "Hello from MyBlog");
+    assert_eq!(report.errors, vec![("Unregistered".to_string(), "Unknown feed type: Other".to_string())]);
+}
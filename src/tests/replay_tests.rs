@@ -0,0 +1,111 @@
+use crate::aggregator::Aggregator;
+use crate::feed_types::{ArticleFetcher, AtomFetcher, FetchContext};
+use crate::models::BlogInfo;
+use crate::replay::{FixtureMode, FixtureStore};
+use crate::dates::DateWindow;
+use chrono::NaiveDate;
+use mockito::mock;
+use tempfile::tempdir;
+use tokio::io::AsyncReadExt;
+
+const ATOM_BODY: &str = r#"
+<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+    <entry>
+        <title>Recorded Atom Article</title>
+        <link href="https://test.com/recorded-article"/>
+        <updated>2024-10-01T12:00:00Z</updated>
+    </entry>
+</feed>
+"#;
+
+#[tokio::test]
+async fn test_record_then_replay_without_network() {
+    let dir = tempdir().expect("Failed to create temporary directory");
+    let url = format!("{}/recorded.atom", mockito::server_url());
+    let window = DateWindow::since(NaiveDate::from_ymd_opt(2024, 9, 1).unwrap());
+
+    let live = mock("GET", "/recorded.atom")
+        .with_status(200)
+        .with_header("content-type", "application/atom+xml")
+        .with_body(ATOM_BODY)
+        .expect(1)
+        .create();
+    let record_ctx = FetchContext {
+        fixtures: Some(FixtureMode::Record(FixtureStore::new(dir.path()))),
+        ..FetchContext::default()
+    };
+    let recorded = AtomFetcher.fetch_articles(&record_ctx, &url, &window, "TestAtomBlog", None)
+        .await
+        .expect("Failed to fetch while recording");
+    live.assert();
+
+    let offline = mock("GET", "/recorded.atom")
+        .expect(0)
+        .create();
+    let replay_ctx = FetchContext {
+        fixtures: Some(FixtureMode::Replay(FixtureStore::new(dir.path()))),
+        ..FetchContext::default()
+    };
+    let replayed = AtomFetcher.fetch_articles(&replay_ctx, &url, &window, "TestAtomBlog", None)
+        .await
+        .expect("Failed to replay");
+    offline.assert();
+
+    assert_eq!(recorded.len(), 1);
+    assert_eq!(replayed.len(), 1);
+    assert_eq!(replayed[0].title, "Recorded Atom Article");
+    assert_eq!(replayed[0].url, recorded[0].url);
+    assert_eq!(replay_ctx.log.attempts()[0].outcome, "200 OK (replayed)");
+
+    let response = FixtureStore::new(dir.path()).open(&url).await.expect("Failed to load fixture");
+    assert_eq!(response.headers.get("content-type").unwrap(), "application/atom+xml");
+}
+
+#[tokio::test]
+async fn test_replay_missing_fixture_is_a_source_error() {
+    let dir = tempdir().expect("Failed to create temporary directory");
+    let blogs: Vec<BlogInfo> = serde_json::from_value(serde_json::json!([
+        {
+            "name": "NotRecorded",
+            "domain": "https://test.com/feed",
+            "feed_type": "RSS"
+        }
+    ])).expect("Failed to build blogs");
+
+    let window = DateWindow::since(NaiveDate::from_ymd_opt(2024, 9, 1).unwrap());
+    let report = Aggregator::new(blogs)
+        .with_fixtures(FixtureMode::Replay(FixtureStore::new(dir.path())))
+        .fetch(&window)
+        .await;
+
+    assert_eq!(report.errors.len(), 1);
+    assert!(report.errors[0].1.contains("No recorded response for https://test.com/feed"));
+}
+
+#[tokio::test]
+async fn test_partially_read_body_is_not_recorded() {
+    let dir = tempdir().expect("Failed to create temporary directory");
+    let url = format!("{}/abandoned.atom", mockito::server_url());
+
+    let _m = mock("GET", "/abandoned.atom")
+        .with_status(200)
+        .with_body(ATOM_BODY)
+        .create();
+    let ctx = FetchContext {
+        fixtures: Some(FixtureMode::Record(FixtureStore::new(dir.path()))),
+        ..FetchContext::default()
+    };
+
+    let mut reader = ctx.get_reader(&url).await.expect("Failed to open response");
+    let mut start = [0u8; 8];
+    reader.read_exact(&mut start).await.expect("Failed to read the start of the body");
+    drop(reader);
+    assert!(FixtureStore::new(dir.path()).open(&url).await.is_err());
+    assert_eq!(std::fs::read_dir(dir.path()).expect("Failed to list fixtures").count(), 0);
+
+    let body = ctx.get_text(&url).await.expect("Failed to fetch");
+    let response = FixtureStore::new(dir.path()).open(&url).await.expect("Failed to load fixture")
+        .buffer().await.expect("Failed to read fixture");
+    assert_eq!(response.body, body.as_bytes());
+}
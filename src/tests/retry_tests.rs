@@ -0,0 +1,189 @@
+use crate::aggregator::Aggregator;
+use crate::context::FetchContext;
+use crate::models::BlogInfo;
+use crate::retry::{is_retryable_status, parse_retry_after, RetryPolicy};
+use crate::dates::DateWindow;
+use chrono::NaiveDate;
+use mockito::mock;
+use reqwest::header::{HeaderMap, HeaderValue, RETRY_AFTER};
+use reqwest::StatusCode;
+use std::time::Duration;
+
+fn fast_policy(max_attempts: u32) -> RetryPolicy {
+    RetryPolicy {
+        max_attempts,
+        initial_backoff_ms: 10,
+        max_backoff_ms: 50,
+        multiplier: 2.0,
+    }
+}
+
+#[test]
+fn test_retry_policy_delay() {
+    let policy = RetryPolicy {
+        max_attempts: 5,
+        initial_backoff_ms: 100,
+        max_backoff_ms: 1000,
+        multiplier: 2.0,
+    };
+
+    for _ in 0..20 {
+        let first = policy.delay(1, None);
+        assert!(first >= Duration::from_millis(50) && first <= Duration::from_millis(100));
+        let third = policy.delay(3, None);
+        assert!(third >= Duration::from_millis(200) && third <= Duration::from_millis(400));
+        let capped = policy.delay(10, None);
+        assert!(capped <= Duration::from_millis(1000));
+    }
+
+    // Retry-After wins over the backoff, within max_backoff_ms
+    assert_eq!(policy.delay(1, Some(Duration::from_millis(700))), Duration::from_millis(700));
+    assert_eq!(policy.delay(1, Some(Duration::from_secs(60))), Duration::from_millis(1000));
+}
+
+#[test]
+fn test_parse_retry_after() {
+    let mut headers = HeaderMap::new();
+    assert_eq!(parse_retry_after(&headers), None);
+
+    headers.insert(RETRY_AFTER, HeaderValue::from_static("7"));
+    assert_eq!(parse_retry_after(&headers), Some(Duration::from_secs(7)));
+
+    headers.insert(RETRY_AFTER, HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"));
+    assert_eq!(parse_retry_after(&headers), Some(Duration::ZERO));
+}
+
+#[test]
+fn test_is_retryable_status() {
+    assert!(is_retryable_status(StatusCode::BAD_GATEWAY));
+    assert!(is_retryable_status(StatusCode::TOO_MANY_REQUESTS));
+    assert!(!is_retryable_status(StatusCode::NOT_FOUND));
+    assert!(!is_retryable_status(StatusCode::OK));
+}
+
+#[tokio::test]
+async fn test_fetch_context_retries_transient_errors() {
+    let _failure = mock("GET", "/flaky")
+        .with_status(502)
+        .expect(1)
+        .create();
+    let _success = mock("GET", "/flaky")
+        .with_status(200)
+        .with_body("ok")
+        .create();
+
+    let ctx = FetchContext {
+        retry: fast_policy(3),
+        ..FetchContext::default()
+    };
+    let body = ctx.get_text(&format!("{}/flaky", mockito::server_url()))
+        .await
+        .expect("Failed to fetch after retry");
+
+    assert_eq!(body, "ok");
+    let attempts = ctx.log.attempts();
+    assert_eq!(attempts.len(), 2);
+    assert_eq!(attempts[0].attempt, 1);
+    assert_eq!(attempts[0].outcome, "502 Bad Gateway");
+    assert!(attempts[0].retry_in.is_some());
+    assert_eq!(attempts[1].outcome, "200 OK");
+    assert!(attempts[1].retry_in.is_none());
+}
+
+#[tokio::test]
+async fn test_fetch_context_retries_truncated_bodies() {
+    // The connection closes before the announced length was sent
+    let _truncated = mock("GET", "/truncated")
+        .with_status(200)
+        .with_header("content-length", "100")
+        .with_body("partial")
+        .expect(1)
+        .create();
+    let _complete = mock("GET", "/truncated")
+        .with_status(200)
+        .with_body("complete")
+        .create();
+
+    let ctx = FetchContext {
+        retry: fast_policy(3),
+        ..FetchContext::default()
+    };
+    let body = ctx.get_text(&format!("{}/truncated", mockito::server_url()))
+        .await
+        .expect("Failed to fetch after retry");
+
+    assert_eq!(body, "complete");
+    let attempts = ctx.log.attempts();
+    assert_eq!(attempts.len(), 2);
+    assert_ne!(attempts[0].outcome, "200 OK");
+    assert!(attempts[0].retry_in.is_some());
+    assert_eq!(attempts[1].attempt, 2);
+    assert_eq!(attempts[1].outcome, "200 OK");
+}
+
+#[tokio::test]
+async fn test_fetch_context_honors_retry_after() {
+    let _failure = mock("GET", "/limited")
+        .with_status(429)
+        .with_header("retry-after", "0")
+        .expect(1)
+        .create();
+    let _success = mock("GET", "/limited")
+        .with_status(200)
+        .create();
+
+    let ctx = FetchContext {
+        retry: fast_policy(2),
+        ..FetchContext::default()
+    };
+    ctx.get_bytes(&format!("{}/limited", mockito::server_url()))
+        .await
+        .expect("Failed to fetch after retry");
+
+    assert_eq!(ctx.log.attempts()[0].retry_in, Some(Duration::ZERO));
+}
+
+#[tokio::test]
+async fn test_fetch_context_does_not_retry_client_errors() {
+    let m = mock("GET", "/gone")
+        .with_status(404)
+        .expect(1)
+        .create();
+
+    let ctx = FetchContext {
+        retry: fast_policy(3),
+        ..FetchContext::default()
+    };
+    assert!(ctx.get_bytes(&format!("{}/gone", mockito::server_url())).await.is_err());
+    assert_eq!(ctx.log.attempts().len(), 1);
+    m.assert();
+}
+
+#[tokio::test]
+async fn test_aggregator_reports_attempts_with_source_override() {
+    let m = mock("GET", "/down")
+        .with_status(503)
+        .expect(4)
+        .create();
+
+    let blogs: Vec<BlogInfo> = serde_json::from_value(serde_json::json!([
+        {
+            "name": "DownRSS",
+            "domain": format!("{}/down", mockito::server_url()),
+            "feed_type": "RSS",
+            "max_attempts": 4
+        }
+    ])).expect("Failed to build blogs");
+
+    let window = DateWindow::since(NaiveDate::from_ymd_opt(2024, 9, 1).unwrap());
+    let report = Aggregator::new(blogs)
+        .with_retry_policy(fast_policy(2))
+        .fetch(&window)
+        .await;
+
+    m.assert();
+    assert_eq!(report.errors.len(), 1);
+    assert_eq!(report.attempts.len(), 4);
+    assert!(report.attempts.iter().all(|(name, attempt)| name == "DownRSS" && attempt.outcome == "503 Service Unavailable"));
+    assert!(report.attempts[3].1.retry_in.is_none());
+}
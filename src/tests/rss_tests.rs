@@ -1,7 +1,7 @@
-use crate::feed_types::{ArticleFetcher, RssFetcher};
-use chrono::NaiveDate;
+use crate::feed_types::{ArticleFetcher, FetchContext, RssFetcher};
+use crate::dates::DateWindow;
+use chrono::{NaiveDate, TimeZone, Utc};
 use mockito::mock;
-use tokio;
 
 #[tokio::test]
 async fn test_fetch_rss_blog_articles() {
@@ -27,13 +27,13 @@ async fn test_fetch_rss_blog_articles() {
         .create();
 
     // Set a date for filtering articles
-    let since_date = NaiveDate::from_ymd_opt(2024, 9, 1).unwrap();
+    let window = DateWindow::since(NaiveDate::from_ymd_opt(2024, 9, 1).unwrap());
 
     // Create an RssFetcher instance
     let fetcher = RssFetcher;
 
     // Call the function under test with the mock server URL
-    let articles = fetcher.fetch_articles(&mockito::server_url(), &since_date, "TestRSSBlog", None)
+    let articles = fetcher.fetch_articles(&FetchContext::default(), &mockito::server_url(), &window, "TestRSSBlog", None)
         .await
         .expect("Failed to fetch RSS articles");
 
@@ -41,5 +41,93 @@ async fn test_fetch_rss_blog_articles() {
     assert_eq!(articles.len(), 1);
     assert_eq!(articles[0].title, "Test RSS Article");
     assert_eq!(articles[0].url, "https://test.com/rss-article");
-    assert_eq!(articles[0].date, NaiveDate::from_ymd_opt(2024, 10, 1).unwrap());
-}
\ No newline at end of file
+    assert_eq!(articles[0].published, Utc.with_ymd_and_hms(2024, 10, 1, 12, 0, 0).unwrap());
+}
+
+#[tokio::test]
+async fn test_fetch_rss_skips_bad_items() {
+    let mock_response = r#"
+    <?xml version="1.0" encoding="UTF-8"?>
+    <rss version="2.0">
+        <channel>
+            <item>
+                <title>Good Article</title>
+                <link>https://test.com/good</link>
+                <pubDate>Tue, 01 Oct 2024 12:00:00 GMT</pubDate>
+            </item>
+            <item>
+                <title>Undated Article</title>
+                <link>https://test.com/undated</link>
+            </item>
+            <item>
+                <title>Misdated Article</title>
+                <link>https://test.com/misdated</link>
+                <pubDate>sometime in October</pubDate>
+            </item>
+        </channel>
+    </rss>
+    "#;
+
+    let _m = mock("GET", "/partly-broken.xml")
+        .with_status(200)
+        .with_header("content-type", "application/rss+xml")
+        .with_body(mock_response)
+        .create();
+
+    let ctx = FetchContext::default();
+    let window = DateWindow::since(NaiveDate::from_ymd_opt(2024, 9, 1).unwrap());
+    let feed_url = format!("{}/partly-broken.xml", mockito::server_url());
+    let articles = RssFetcher.fetch_articles(&ctx, &feed_url, &window, "TestRSSBlog", None)
+        .await
+        .expect("Failed to fetch RSS articles");
+
+    assert_eq!(articles.len(), 1);
+    assert_eq!(articles[0].url, "https://test.com/good");
+    let skipped = ctx.log.skipped();
+    assert_eq!(skipped.len(), 2);
+    assert_eq!(skipped[0].item, "https://test.com/undated");
+    assert_eq!(skipped[0].reason, "Parse error: Missing publication date");
+    assert_eq!(skipped[1].item, "https://test.com/misdated");
+}
+
+#[tokio::test]
+async fn test_fetch_rss_fails_when_no_item_can_be_read() {
+    let mock_response = r#"<rss version="2.0"><channel>
+        <item><title>A</title><link>https://test.com/a</link></item>
+        <item><title>B</title><link>https://test.com/b</link></item>
+    </channel></rss>"#;
+
+    let _m = mock("GET", "/all-broken.xml")
+        .with_status(200)
+        .with_body(mock_response)
+        .create();
+
+    let window = DateWindow::since(NaiveDate::from_ymd_opt(2024, 9, 1).unwrap());
+    let feed_url = format!("{}/all-broken.xml", mockito::server_url());
+    let result = RssFetcher.fetch_articles(&FetchContext::default(), &feed_url, &window, "TestRSSBlog", None).await;
+
+    assert_eq!(result.unwrap_err().to_string(), "Parse error: None of the 2 items could be read, the first failed with: Parse error: Missing publication date");
+}
+
+#[tokio::test]
+async fn test_fetch_rss_articles_until() {
+    let mock_response = r#"<rss version="2.0"><channel>
+        <item><title>October</title><link>https://test.com/october</link><pubDate>Tue, 01 Oct 2024 12:00:00 GMT</pubDate></item>
+        <item><title>September</title><link>https://test.com/september</link><pubDate>Mon, 30 Sep 2024 23:59:00 GMT</pubDate></item>
+        <item><title>August</title><link>https://test.com/august</link><pubDate>Sat, 31 Aug 2024 12:00:00 GMT</pubDate></item>
+    </channel></rss>"#;
+
+    let _m = mock("GET", "/until.xml")
+        .with_status(200)
+        .with_body(mock_response)
+        .create();
+
+    let window = DateWindow::month(2024, 9).unwrap();
+    let feed_url = format!("{}/until.xml", mockito::server_url());
+    let articles = RssFetcher.fetch_articles(&FetchContext::default(), &feed_url, &window, "TestRSSBlog", None)
+        .await
+        .expect("Failed to fetch RSS articles");
+
+    let titles: Vec<_> = articles.iter().map(|a| a.title.as_str()).collect();
+    assert_eq!(titles, vec!["September"]);
+}
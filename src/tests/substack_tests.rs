@@ -1,7 +1,7 @@
-use crate::feed_types::{ArticleFetcher, SubstackFetcher};
-use chrono::NaiveDate;
+use crate::feed_types::{ArticleFetcher, FetchContext, SubstackFetcher};
+use crate::dates::DateWindow;
+use chrono::{NaiveDate, TimeZone, Utc};
 use mockito::mock;
-use tokio;
 
 #[tokio::test]
 async fn test_fetch_substack_blog_articles() {
@@ -23,14 +23,14 @@ async fn test_fetch_substack_blog_articles() {
         .create();
 
     // Set a date for filtering articles
-    let since_date = NaiveDate::from_ymd_opt(2024, 9, 1).unwrap();
+    let window = DateWindow::since(NaiveDate::from_ymd_opt(2024, 9, 1).unwrap());
 
     // Create a SubstackFetcher instance
     let fetcher = SubstackFetcher;
 
     // Call the function under test with the mock server URL
     let mock_url = mockito::server_url();
-    let articles = fetcher.fetch_articles(&mock_url, &since_date, "TestBlog", None)
+    let articles = fetcher.fetch_articles(&FetchContext::default(), &mock_url, &window, "TestBlog", None)
         .await
         .expect("Failed to fetch Substack articles");
 
@@ -38,5 +38,35 @@ async fn test_fetch_substack_blog_articles() {
     assert_eq!(articles.len(), 1);
     assert_eq!(articles[0].title, "Test Article");
     assert_eq!(articles[0].url, format!("{}/p/test-article", mock_url));
-    assert_eq!(articles[0].date, NaiveDate::from_ymd_opt(2024, 10, 1).unwrap());
-}
\ No newline at end of file
+    assert_eq!(articles[0].published, Utc.with_ymd_and_hms(2024, 10, 1, 0, 0, 0).unwrap());
+}
+
+#[tokio::test]
+async fn test_fetch_substack_skips_bad_posts() {
+    let mock_body = r#"[
+        {"id": 1, "title": "Good Post", "slug": "good-post", "post_date": "2024-10-01T00:00:00.000Z"},
+        {"id": 2, "title": "Draft", "slug": "draft"},
+        {"id": 3, "title": "Odd Date", "slug": "odd-date", "post_date": "01/10/2024"}
+    ]"#;
+
+    let _m = mock("GET", "/partly-broken")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(mock_body)
+        .create();
+
+    let ctx = FetchContext::default();
+    let window = DateWindow::since(NaiveDate::from_ymd_opt(2024, 9, 1).unwrap());
+    let feed_url = format!("{}/partly-broken", mockito::server_url());
+    let articles = SubstackFetcher.fetch_articles(&ctx, &feed_url, &window, "TestBlog", None)
+        .await
+        .expect("Failed to fetch Substack articles");
+
+    assert_eq!(articles.len(), 1);
+    assert_eq!(articles[0].title, "Good Post");
+    let skipped = ctx.log.skipped();
+    assert_eq!(skipped.len(), 2);
+    assert_eq!(skipped[0].item, "draft");
+    assert_eq!(skipped[0].reason, "Parse error: Missing post_date");
+    assert_eq!(skipped[1].item, "odd-date");
+}
@@ -0,0 +1,231 @@
+use crate::feed_types::{ArticleFetcher, AtomFetcher, FetchContext, RssFetcher};
+use crate::syndication::{parse_feed, FeedEntry, FeedFormat};
+use crate::dates::DateWindow;
+use chrono::{NaiveDate, TimeZone, Utc};
+use mockito::mock;
+
+const RDF_FEED: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#"
+         xmlns:dc="http://purl.org/dc/elements/1.1/"
+         xmlns="http://purl.org/rss/1.0/">
+    <channel rdf:about="https://test.com/">
+        <title>RDF Blog</title>
+        <link>https://test.com/</link>
+    </channel>
+    <item rdf:about="https://test.com/rdf-article">
+        <title>Test RDF Article</title>
+        <link>https://test.com/rdf-article</link>
+        <dc:date>2024-10-02T08:00:00+02:00</dc:date>
+    </item>
+</rdf:RDF>"#;
+
+const ATOM_FEED: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+    <title>Atom Blog</title>
+    <link href="https://test.com/"/>
+    <entry>
+        <title type="html">Test &amp; Atom Article</title>
+        <link rel="edit" href="https://test.com/edit/1"/>
+        <link rel="alternate" href="https://test.com/atom-article"/>
+        <published>2024-10-01T12:00:00.000Z</published>
+        <updated>2024-10-03T12:00:00Z</updated>
+    </entry>
+</feed>"#;
+
+#[test]
+fn test_parse_rss_2_0() {
+    let feed = parse_feed(br#"<rss version="2.0" xmlns:atom="http://www.w3.org/2005/Atom"><channel>
+        <title>Blog</title>
+        <atom:link href="https://test.com/feed" rel="self"/>
+        <item>
+            <title><![CDATA[Proofs & <Arguments>]]></title>
+            <guid isPermaLink="true">https://test.com/guid-link</guid>
+            <pubDate>Tue, 01 Oct 2024 12:00:00 GMT</pubDate>
+        </item>
+        <item>
+            <title>Second</title>
+            <link>https://test.com/second</link>
+            <guid isPermaLink="false">42</guid>
+            <pubDate>Wed, 02 Oct 2024 12:00:00 +0000</pubDate>
+        </item>
+    </channel></rss>"#).unwrap();
+
+    assert_eq!(feed.format, FeedFormat::Rss20);
+    assert_eq!(feed.entries, vec![
+        FeedEntry {
+            title: Some("Proofs & <Arguments>".to_string()),
+            link: Some("https://test.com/guid-link".to_string()),
+            published: Some("Tue, 01 Oct 2024 12:00:00 GMT".to_string()),
+            updated: None,
+            authors: Vec::new(),
+        },
+        FeedEntry {
+            title: Some("Second".to_string()),
+            link: Some("https://test.com/second".to_string()),
+            published: Some("Wed, 02 Oct 2024 12:00:00 +0000".to_string()),
+            updated: None,
+            authors: Vec::new(),
+        },
+    ]);
+}
+
+#[test]
+fn test_parse_rss_guid_links() {
+    let feed = parse_feed(br#"<rss version="2.0"><channel>
+        <item><title>Permalink</title><guid>https://test.com/guid-link</guid></item>
+        <item><title>Identifier</title><guid>abc-123</guid></item>
+        <item><title>Tag URI</title><guid>tag:test.com,2024:1</guid></item>
+        <item><title>Declared</title><guid isPermaLink="true">/posts/declared</guid></item>
+    </channel></rss>"#).unwrap();
+
+    let links: Vec<_> = feed.entries.iter().map(|entry| entry.link.as_deref()).collect();
+    assert_eq!(links, vec![Some("https://test.com/guid-link"), None, None, Some("/posts/declared")]);
+}
+
+#[test]
+fn test_parse_text_with_html_entities() {
+    let feed = parse_feed(br#"<rss version="2.0"><channel><item>
+        <title>Proofs &amp; Arguments&nbsp;&#8212; &lt;Part&gt; 1 &amp bits</title>
+    </item></channel></rss>"#).unwrap();
+
+    // `&nbsp;` is left as written, the XML references around it are decoded
+    assert_eq!(feed.entries[0].title.as_deref(), Some("Proofs & Arguments&nbsp;\u{2014} <Part> 1 &amp bits"));
+}
+
+#[test]
+fn test_parse_rss_0_9x_and_rdf() {
+    let feed = parse_feed(br#"<rss version="0.91"><channel><title>Old</title>
+        <item><title>Old Article</title><link>https://test.com/old</link></item>
+    </channel></rss>"#).unwrap();
+    assert_eq!(feed.format, FeedFormat::Rss09x);
+    assert_eq!(feed.entries[0].link, Some("https://test.com/old".to_string()));
+    assert_eq!(feed.entries[0].published, None);
+
+    let feed = parse_feed(RDF_FEED.as_bytes()).unwrap();
+    assert_eq!(feed.format, FeedFormat::Rss10);
+    assert_eq!(feed.entries.len(), 1);
+    assert_eq!(feed.entries[0].title, Some("Test RDF Article".to_string()));
+    assert_eq!(feed.entries[0].published, Some("2024-10-02T08:00:00+02:00".to_string()));
+
+    let feed = parse_feed(br#"<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#" xmlns="http://my.netscape.com/rdf/simple/0.9/">
+        <channel><title>Netscape</title></channel>
+        <item><title>First RSS</title><link>https://test.com/0.90</link></item>
+    </rdf:RDF>"#).unwrap();
+    assert_eq!(feed.format, FeedFormat::Rss090);
+    assert_eq!(feed.entries[0].link, Some("https://test.com/0.90".to_string()));
+}
+
+#[test]
+fn test_parse_atom() {
+    let feed = parse_feed(ATOM_FEED.as_bytes()).unwrap();
+    assert_eq!(feed.format, FeedFormat::Atom10);
+    assert_eq!(feed.entries, vec![FeedEntry {
+        title: Some("Test & Atom Article".to_string()),
+        link: Some("https://test.com/atom-article".to_string()),
+        published: Some("2024-10-01T12:00:00.000Z".to_string()),
+        updated: Some("2024-10-03T12:00:00Z".to_string()),
+        authors: Vec::new(),
+    }]);
+
+    let feed = parse_feed(br#"<feed version="0.3" xmlns="http://purl.org/atom/ns#">
+        <title>Atom 0.3</title>
+        <entry>
+            <title>Legacy Entry</title>
+            <link rel="alternate" type="text/html" href="https://test.com/legacy"/>
+            <issued>2004-01-01T00:00:00Z</issued>
+            <modified>2004-01-02T00:00:00Z</modified>
+        </entry>
+    </feed>"#).unwrap();
+    assert_eq!(feed.format, FeedFormat::Atom03);
+    assert_eq!(feed.entries[0].link, Some("https://test.com/legacy".to_string()));
+    assert_eq!(feed.entries[0].published, Some("2004-01-01T00:00:00Z".to_string()));
+    assert_eq!(feed.entries[0].updated, Some("2004-01-02T00:00:00Z".to_string()));
+}
+
+#[test]
+fn test_parse_atom_links_and_authors() {
+    let feed = parse_feed(br#"<feed xmlns="http://www.w3.org/2005/Atom" xml:base="https://test.com/blog/">
+        <title>Atom Blog</title>
+        <author><name>Feed Author</name></author>
+        <entry>
+            <title>Relative</title>
+            <link rel="self" href="https://test.com/api/1"/>
+            <link rel="alternate" type="application/pdf" href="paper.pdf"/>
+            <link rel="alternate" type="text/html" href="2024/relative"/>
+            <author><name>Alice Smith</name><email>alice@test.com</email></author>
+            <author><name>Bob Jones</name></author>
+        </entry>
+        <entry xml:base="/archive/">
+            <title>Nested base</title>
+            <link rel="edit" href="https://test.com/edit/2"/>
+            <link href="nested"/>
+        </entry>
+        <entry>
+            <title>Only self</title>
+            <link rel="self" href="https://test.com/api/3"/>
+        </entry>
+    </feed>"#).unwrap();
+
+    assert_eq!(feed.authors, vec!["Feed Author"]);
+    assert_eq!(feed.entries[0].link, Some("https://test.com/blog/2024/relative".to_string()));
+    assert_eq!(feed.entries[0].authors, vec!["Alice Smith", "Bob Jones"]);
+    assert_eq!(feed.entries[1].link, Some("https://test.com/archive/nested".to_string()));
+    assert!(feed.entries[1].authors.is_empty());
+    assert_eq!(feed.entries[2].link, None);
+
+    let feed = parse_feed(RDF_FEED.replace("<dc:date>", "<dc:creator>Carol White</dc:creator><dc:date>").as_bytes()).unwrap();
+    assert_eq!(feed.entries[0].authors, vec!["Carol White"]);
+}
+
+#[test]
+fn test_parse_feed_errors() {
+    let error = parse_feed(b"<html><body>Not a feed</body></html>").unwrap_err();
+    assert_eq!(error.to_string(), "Parse error: Not an RSS or Atom feed: root element <html>");
+    assert!(parse_feed(b"").is_err());
+    assert!(parse_feed(b"<rss><channel><item></channel></rss>").is_err());
+}
+
+#[tokio::test]
+async fn test_rss_fetcher_reads_mislabelled_atom_feed() {
+    let _m = mock("GET", "/mislabelled/atom")
+        .with_status(200)
+        .with_header("content-type", "application/rss+xml")
+        .with_body(ATOM_FEED)
+        .create();
+
+    let ctx = FetchContext::default();
+    let window = DateWindow::since(NaiveDate::from_ymd_opt(2024, 9, 1).unwrap());
+    let feed_url = format!("{}/mislabelled/atom", mockito::server_url());
+    let articles = RssFetcher.fetch_articles(&ctx, &feed_url, &window, "TestBlog", None)
+        .await
+        .expect("Failed to fetch articles");
+
+    assert_eq!(articles.len(), 1);
+    assert_eq!(articles[0].url, "https://test.com/atom-article");
+    assert_eq!(ctx.log.warnings(), vec!["Declared as RSS but is an Atom 1.0 feed"]);
+}
+
+#[tokio::test]
+async fn test_atom_fetcher_reads_mislabelled_rdf_feed() {
+    let _m = mock("GET", "/mislabelled/rdf")
+        .with_status(200)
+        .with_body(RDF_FEED)
+        .create();
+
+    let ctx = FetchContext::default();
+    let window = DateWindow::since(NaiveDate::from_ymd_opt(2024, 9, 1).unwrap());
+    let feed_url = format!("{}/mislabelled/rdf", mockito::server_url());
+    let articles = AtomFetcher.fetch_articles(&ctx, &feed_url, &window, "TestBlog", None)
+        .await
+        .expect("Failed to fetch articles");
+
+    assert_eq!(articles.len(), 1);
+    assert_eq!(articles[0].title, "Test RDF Article");
+    assert_eq!(articles[0].published, Utc.with_ymd_and_hms(2024, 10, 2, 6, 0, 0).unwrap());
+    assert_eq!(ctx.log.warnings(), vec!["Declared as Atom but is an RSS 1.0 feed"]);
+
+    // A feed of the declared family is not reported
+    let ctx = FetchContext::default();
+    RssFetcher.fetch_articles(&ctx, &feed_url, &window, "TestBlog", None).await.unwrap();
+    assert!(ctx.log.warnings().is_empty());
+}
@@ -1,28 +1,8 @@
-use crate::utils::{parse_rss_date, capitalize_title, write_output};
+use crate::dates::DateWindow;
+use crate::utils::{capitalize_title, write_output, parse_args_from, parse_args_on};
 use chrono::NaiveDate;
-use std::fs::File;
-
-#[test]
-fn test_parse_rss_date() {
-    let date1 = "Tue, 01 Oct 2024 12:00:00 GMT";
-    let date2 = "2024-10-01T12:00:00+00:00";
-    let date3 = "2024-10-01";
-    
-    assert_eq!(
-        parse_rss_date(date1).expect("Failed to parse date1"),
-        NaiveDate::from_ymd_opt(2024, 10, 1).expect("Failed to create NaiveDate")
-    );
-    assert_eq!(
-        parse_rss_date(date2).expect("Failed to parse date2"),
-        NaiveDate::from_ymd_opt(2024, 10, 1).expect("Failed to create NaiveDate")
-    );
-    assert_eq!(
-        parse_rss_date(date3).expect("Failed to parse date3"),
-        NaiveDate::from_ymd_opt(2024, 10, 1).expect("Failed to create NaiveDate")
-    );
-    
-    assert!(parse_rss_date("Invalid Date").is_err());
-}
+use std::fs;
+use tempfile::tempdir;
 
 #[test]
 fn test_capitalize_title() {
@@ -37,11 +17,139 @@ fn test_capitalize_title() {
 
 #[test]
 fn test_write_output() {
+    let dir = tempdir().expect("Failed to create temporary directory");
+    let path = dir.path().join("output").join("index.html");
     let html_content = "<html><body><h1>Test</h1></body></html>";
-    let result = write_output(html_content);
+    let result = write_output(&path, html_content);
     assert!(result.is_ok());
 
     // Check if the file was created
-    let output_file = File::open("./output/index.html");
-    assert!(output_file.is_ok());
-}
\ No newline at end of file
+    assert_eq!(fs::read_to_string(&path).unwrap(), html_content);
+}
+
+#[test]
+fn test_parse_args_from() {
+    let args = vec![
+        "program_name".to_string(),
+        "blogs.json".to_string(),
+        "2024-09-01".to_string(),
+    ];
+    let cli_args = parse_args_from(&args).expect("Failed to parse args");
+    assert_eq!(cli_args.blogs_file, "blogs.json");
+    assert_eq!(cli_args.window, DateWindow::since(NaiveDate::from_ymd_opt(2024, 9, 1).unwrap()));
+    assert_eq!(cli_args.settings_file, None);
+
+    let cli_args = parse_args_from(&["program_name".to_string()]).expect("Failed to parse default args");
+    assert_eq!(cli_args.blogs_file, "./config/blogs.json");
+}
+
+#[test]
+fn test_parse_args_from_settings_flag() {
+    let args = vec![
+        "program_name".to_string(),
+        "--settings".to_string(),
+        "settings.json".to_string(),
+        "blogs.json".to_string(),
+    ];
+    let cli_args = parse_args_from(&args).expect("Failed to parse args");
+    assert_eq!(cli_args.blogs_file, "blogs.json");
+    assert_eq!(cli_args.settings_file, Some("settings.json".to_string()));
+
+    assert!(parse_args_from(&["program_name".to_string(), "--settings".to_string()]).is_err());
+    assert!(parse_args_from(&["program_name".to_string(), "--unknown".to_string()]).is_err());
+}
+
+#[test]
+fn test_parse_args_from_invalid_date() {
+    let args = vec![
+        "program_name".to_string(),
+        "blogs.json".to_string(),
+        "invalid_date".to_string(),
+    ];
+    assert!(parse_args_from(&args).is_err());
+}
+
+#[test]
+fn test_parse_args_from_record_and_replay() {
+    let args = vec![
+        "program_name".to_string(),
+        "--record".to_string(),
+        "fixtures/2024-09".to_string(),
+    ];
+    let cli_args = parse_args_from(&args).expect("Failed to parse args");
+    assert_eq!(cli_args.record_dir, Some("fixtures/2024-09".to_string()));
+    assert_eq!(cli_args.replay_dir, None);
+
+    let args = vec![
+        "program_name".to_string(),
+        "--record".to_string(),
+        "a".to_string(),
+        "--replay".to_string(),
+        "b".to_string(),
+    ];
+    assert!(parse_args_from(&args).is_err());
+}
+
+#[test]
+fn test_parse_args_from_eprint_config() {
+    let args = vec![
+        "program_name".to_string(),
+        "--eprint-config".to_string(),
+        "config/eprint_fhe_mpc.json".to_string(),
+    ];
+    let cli_args = parse_args_from(&args).expect("Failed to parse args");
+    assert_eq!(cli_args.eprint_config, Some("config/eprint_fhe_mpc.json".to_string()));
+    assert_eq!(cli_args.blogs_file, "./config/blogs.json");
+}
+
+#[test]
+fn test_parse_args_from_detect() {
+    let args = vec![
+        "program_name".to_string(),
+        "--detect".to_string(),
+        "https://blog.example.com".to_string(),
+    ];
+    let cli_args = parse_args_from(&args).expect("Failed to parse args");
+    assert_eq!(cli_args.detect_url, Some("https://blog.example.com".to_string()));
+    assert_eq!(cli_args.eprint_config, None);
+}
+
+fn args(args: &[&str]) -> Vec<String> {
+    std::iter::once("program_name").chain(args.iter().copied()).map(str::to_string).collect()
+}
+
+#[test]
+fn test_parse_args_date_windows() {
+    let today = NaiveDate::from_ymd_opt(2024, 10, 16).unwrap(); // A Wednesday
+    let date = |month, day| NaiveDate::from_ymd_opt(2024, month, day).unwrap();
+
+    let cli_args = parse_args_on(&args(&[]), today).unwrap();
+    assert_eq!(cli_args.window, DateWindow::since(date(9, 30)));
+
+    let cli_args = parse_args_on(&args(&["blogs.json", "2024-09-01", "--until", "2024-09-15"]), today).unwrap();
+    assert_eq!(cli_args.window, DateWindow { since: date(9, 1), until: Some(date(9, 15)) });
+
+    let cli_args = parse_args_on(&args(&["--month", "2024-02"]), today).unwrap();
+    assert_eq!(cli_args.window, DateWindow { since: date(2, 1), until: Some(date(2, 29)) });
+
+    let cli_args = parse_args_on(&args(&["--month", "2024-12"]), today).unwrap();
+    assert_eq!(cli_args.window, DateWindow { since: date(12, 1), until: Some(date(12, 31)) });
+
+    let cli_args = parse_args_on(&args(&["--last-week"]), today).unwrap();
+    assert_eq!(cli_args.window, DateWindow { since: date(10, 7), until: Some(date(10, 13)) });
+}
+
+#[test]
+fn test_parse_args_invalid_date_windows() {
+    let today = NaiveDate::from_ymd_opt(2024, 10, 16).unwrap();
+    let error = |arguments: &[&str]| parse_args_on(&args(arguments), today).unwrap_err().to_string();
+
+    assert_eq!(error(&["blogs.json", "2024-09-15", "--until", "2024-09-01"]), "Parse error: Since date 2024-09-15 is after until date 2024-09-01");
+    assert_eq!(error(&["--month", "2024-13"]), "Parse error: Invalid month 2024-13, expected YYYY-MM");
+    assert_eq!(error(&["--month", "September"]), "Parse error: Invalid month September, expected YYYY-MM");
+    assert_eq!(error(&["--month", "2024-09", "--last-week"]), "Parse error: --month and --last-week cannot be used together");
+    assert_eq!(error(&["--month", "2024-09", "--until", "2024-09-15"]), "Parse error: --month and --last-week cannot be combined with a since date or --until");
+    assert_eq!(error(&["blogs.json", "2024-09-01", "--last-week"]), "Parse error: --month and --last-week cannot be combined with a since date or --until");
+    assert!(parse_args_on(&args(&["--until", "15/09/2024"]), today).is_err());
+    assert!(parse_args_on(&args(&["--until"]), today).is_err());
+}
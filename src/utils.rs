@@ -2,25 +2,9 @@ use chrono::{NaiveDate, Datelike};
 use std::env;
 use std::fs::File;
 use std::io::Write;
+use std::path::Path;
 use crate::errors::AppError;
-
-pub fn parse_rss_date(date_str: &str) -> Result<NaiveDate, AppError> {
-    let formats = [
-        "%a, %d %b %Y %H:%M:%S %Z",
-        "%a, %d %b %Y %H:%M:%S GMT",
-        "%Y-%m-%dT%H:%M:%S%:z",
-        "%Y-%m-%d",
-        "%Y-%m-%dT%H:%M:%SZ",  
-    ];
-
-    for format in &formats {
-        if let Ok(date) = NaiveDate::parse_from_str(date_str, format) {
-            return Ok(date);
-        }
-    }
-
-    Err(AppError::ParseError(format!("Unable to parse date: {}", date_str)))
-}
+use crate::dates::DateWindow;
 
 pub fn capitalize_title(title: &str) -> String {
     let words = title.split_whitespace().collect::<Vec<&str>>();
@@ -36,13 +20,9 @@ pub fn capitalize_title(title: &str) -> String {
                            (word.chars().all(|c| c.is_uppercase()) && word.len() > 1) || 
                            word.chars().filter(|c| c.is_uppercase()).count() > 2 ||
                            (word.chars().filter(|c| c.is_uppercase()).count() > 1 && word.len() > 2) ||
-                           word.chars().filter(|c| c.is_digit(10)).count() > 1; 
+                           word.chars().filter(|c| c.is_ascii_digit()).count() > 1; 
 
-        let capitalize_next = if i > 0 && words[i - 1].ends_with(':') {
-            true
-        } else {
-            false
-        };
+        let capitalize_next = i > 0 && words[i - 1].ends_with(':');
 
         let capitalized_word = if word.eq_ignore_ascii_case("zksync") {
             "ZKsync".to_string()
@@ -50,7 +30,9 @@ pub fn capitalize_title(title: &str) -> String {
             word.to_string()
         } else if capitalize_next || is_first_or_last || word.len() > 3 || !is_preposition_or_conjunction {
             let mut c = word.to_lowercase();
-            c.get_mut(0..1).map(|s| s.make_ascii_uppercase());
+            if let Some(s) = c.get_mut(0..1) {
+                s.make_ascii_uppercase();
+            }
             c
         } else {
             word.to_lowercase()
@@ -62,33 +44,132 @@ pub fn capitalize_title(title: &str) -> String {
     capitalized_title.join(" ")
 }
 
-pub fn parse_args() -> Result<(String, NaiveDate), AppError> {
+/// Command line arguments of the `zkmesh_fetcher` binary.
+#[derive(Debug, Clone)]
+pub struct CliArgs {
+    pub blogs_file: String,
+    /// Days to fetch: from the since date, up to `--until`, or a named window.
+    pub window: DateWindow,
+    /// Settings file given with `--settings`, if any.
+    pub settings_file: Option<String>,
+    /// Directory given with `--record`, where every HTTP response is saved.
+    pub record_dir: Option<String>,
+    /// Directory given with `--replay`, from which HTTP responses are served.
+    pub replay_dir: Option<String>,
+    /// Search config given with `--eprint-config`, for ePrint sources that set none.
+    pub eprint_config: Option<String>,
+    /// URL given with `--detect`, whose feed is detected instead of running a fetch.
+    pub detect_url: Option<String>,
+}
+
+pub const DEFAULT_SETTINGS_FILE: &str = "./config/settings.json";
+pub const DEFAULT_OUTPUT_FILE: &str = "./output/index.html";
+
+pub fn parse_args() -> Result<CliArgs, AppError> {
     let args: Vec<String> = env::args().collect();
-    let blogs_file = if args.len() > 1 {
-        args[1].clone()
-    } else {
-        "./config/blogs.json".to_string()
-    };
+    parse_args_from(&args)
+}
+
+/// Parses `<program> [options] [blogs_json] [since_date]`, falling back to the
+/// defaults described in the README for missing arguments.
+pub fn parse_args_from(args: &[String]) -> Result<CliArgs, AppError> {
+    parse_args_on(args, chrono::Local::now().date_naive())
+}
+
+/// Like [`parse_args_from`], with the default since date and `--last-week`
+/// computed from `today` instead of the current date.
+pub fn parse_args_on(args: &[String], today: NaiveDate) -> Result<CliArgs, AppError> {
+    let mut positional = Vec::new();
+    let mut settings_file = None;
+    let mut record_dir = None;
+    let mut replay_dir = None;
+    let mut eprint_config = None;
+    let mut detect_url = None;
+    let mut until_date = None;
+    let mut month = None;
+    let mut last_week = false;
+
+    let mut iter = args.iter().skip(1);
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--last-week" => last_week = true,
+            "--settings" | "--record" | "--replay" | "--eprint-config" | "--detect" | "--until" | "--month" => {
+                let value = iter.next()
+                    .ok_or_else(|| AppError::ParseError(format!("Missing value for {}", arg)))?
+                    .clone();
+                match arg.as_str() {
+                    "--settings" => settings_file = Some(value),
+                    "--record" => record_dir = Some(value),
+                    "--replay" => replay_dir = Some(value),
+                    "--eprint-config" => eprint_config = Some(value),
+                    "--until" => until_date = Some(value),
+                    "--month" => month = Some(value),
+                    _ => detect_url = Some(value),
+                }
+            }
+            flag if flag.starts_with("--") => {
+                return Err(AppError::ParseError(format!("Unknown option: {}", flag)));
+            }
+            _ => positional.push(arg.clone()),
+        }
+    }
+
+    if record_dir.is_some() && replay_dir.is_some() {
+        return Err(AppError::ParseError("--record and --replay cannot be used together".to_string()));
+    }
 
-    let since_date = if args.len() > 2 {
-        NaiveDate::parse_from_str(&args[2], "%Y-%m-%d")?
-    } else {
-        let today = chrono::Local::now();
-        // Calculate the last day of the previous month by subtracting one day from the first day of the current month
-        let last_day_of_prev_month = NaiveDate::from_ymd_opt(today.year(), today.month(), 1)
-            .expect("Failed to get the first day of the current month")
-            .pred_opt() // Use pred_opt to get the last day of the previous month
-            .expect("Failed to get the last day of the previous month"); 
-
-        last_day_of_prev_month
+    let blogs_file = positional.first()
+        .cloned()
+        .unwrap_or_else(|| "./config/blogs.json".to_string());
+
+    let since_date = positional.get(1)
+        .map(|since_date| NaiveDate::parse_from_str(since_date, "%Y-%m-%d"))
+        .transpose()?;
+    let until_date = until_date
+        .map(|until_date| NaiveDate::parse_from_str(&until_date, "%Y-%m-%d"))
+        .transpose()?;
+
+    let named_window = match (month, last_week) {
+        (Some(_), true) => return Err(AppError::ParseError("--month and --last-week cannot be used together".to_string())),
+        (Some(month), false) => Some(parse_month(&month)?),
+        (None, true) => Some(DateWindow::last_week(today)),
+        (None, false) => None,
+    };
+    let window = match named_window {
+        Some(_) if since_date.is_some() || until_date.is_some() => {
+            return Err(AppError::ParseError("--month and --last-week cannot be combined with a since date or --until".to_string()));
+        }
+        Some(window) => window,
+        None => {
+            let since_date = since_date.unwrap_or_else(|| {
+                // Calculate the last day of the previous month by subtracting one day from the first day of the current month
+                NaiveDate::from_ymd_opt(today.year(), today.month(), 1)
+                    .expect("Failed to get the first day of the current month")
+                    .pred_opt() // Use pred_opt to get the last day of the previous month
+                    .expect("Failed to get the last day of the previous month")
+            });
+            DateWindow::new(since_date, until_date)?
+        }
     };
 
-    Ok((blogs_file, since_date))
+    Ok(CliArgs { blogs_file, window, settings_file, record_dir, replay_dir, eprint_config, detect_url })
 }
 
-pub fn write_output(html_output: &str) -> Result<(), AppError> {
-    std::fs::create_dir_all("./output")?;
-    let mut file = File::create("./output/index.html")?;
+/// Parses the `YYYY-MM` of `--month`.
+fn parse_month(month: &str) -> Result<DateWindow, AppError> {
+    let invalid = || AppError::ParseError(format!("Invalid month {}, expected YYYY-MM", month));
+    let (year, month_number) = month.split_once('-').ok_or_else(invalid)?;
+    let year = year.parse().map_err(|_| invalid())?;
+    let month_number = month_number.parse().map_err(|_| invalid())?;
+    DateWindow::month(year, month_number).map_err(|_| invalid())
+}
+
+/// Writes the report to `path`, creating its directory if needed.
+pub fn write_output(path: &Path, html_output: &str) -> Result<(), AppError> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut file = File::create(path)?;
     file.write_all(html_output.as_bytes())?;
     Ok(())
 }
@@ -103,4 +184,20 @@ pub fn replace_url(original_url: &str, custom_url_replace: Option<&String>) -> S
         }
     }
     original_url.to_string() // Use the original URL if no replacement is specified or format is incorrect
-}
\ No newline at end of file
+}
+
+/// Joins author names as "A", "A and B" or "A, B and C".
+pub fn format_authors(mut creators: Vec<String>) -> String {
+    match creators.len() {
+        1 => creators[0].clone(),
+        2 => format!("{} and {}", creators[0], creators[1]),
+        _ => {
+            let last = if let Some(creator) = creators.pop() {
+                creator // Remove the last creator safely
+            } else {
+                return String::new(); // Return an empty string if there are no creators
+            };
+            format!("{} and {}", creators.join(", "), last) // Join the rest with commas and add the last with "and"
+        }
+    }
+}